@@ -0,0 +1,115 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Compares inference on boxed and interned types on large generated programs,
+// by the time taken and by the allocations made.
+//
+// $ cargo bench --bench intern
+//
+// On a release build, the 800 bindings program went from 47.0M allocations
+// (5.3GB) and 5.5s with boxed types to 8.2M allocations (878MB) and 0.77s
+// with interned types:
+//
+//  bindings   engine         time       allocs          bytes
+//        50    boxed   12.61458ms       132380        9427039
+//        50 interned   3.594209ms        25537        3023736
+//       200    boxed 303.959528ms      2949433      239738700
+//       200 interned  71.662388ms       533808       58194715
+//       800    boxed 5.493886195s     47000425     5315845849
+//       800 interned  768.31448ms      8207478      877694404
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use type_infer_rs::{
+    expr::{expr::{Expr, Ident}, lit::Lit},
+    gen::Gen,
+    infer::InferCtxt,
+    intern,
+    ty::ty_ctxt::TyCtxt,
+};
+
+/// The system allocator, counting the allocations and the bytes allocated.
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// A chain of `let`s binding generated expressions of random types.
+fn program(seed: u64, bindings: usize, size: usize) -> Expr {
+    let mut gen = Gen::new(seed);
+    let values = (0..bindings)
+        .filter_map(|_| {
+            let ty = gen.gen_ty(6);
+            gen.gen_expr(&TyCtxt::new(), &ty, size)
+        })
+        .collect::<Vec<_>>();
+    values.into_iter()
+        .enumerate()
+        .rev()
+        .fold(Expr::mk_lit(Lit::Int(0)), |body, (i, value)| {
+            Expr::mk_let(Ident::new(format!("v{i}")), value, body)
+        })
+        .assign_ids()
+}
+
+/// Runs inference once, and returns the time taken, the number of
+/// allocations and the bytes allocated.
+fn measure(infer: impl FnOnce()) -> (Duration, usize, usize) {
+    let allocs = ALLOCS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+    infer();
+    let elapsed = start.elapsed();
+    (
+        elapsed,
+        ALLOCS.load(Ordering::Relaxed) - allocs,
+        BYTES.load(Ordering::Relaxed) - bytes,
+    )
+}
+
+fn main() {
+    println!("{:>9} {:>8} {:>12} {:>12} {:>14}", "bindings", "engine", "time", "allocs", "bytes");
+    for (bindings, size) in [(50, 12), (200, 16), (800, 16)] {
+        let expr = program(0x9e37_79b9_7f4a_7c15, bindings, size);
+        let (boxed_ty, interned_ty) = (
+            InferCtxt::new().infer(&expr).unwrap(),
+            intern::infer::InferCtxt::new().infer(&expr).unwrap(),
+        );
+        assert_eq!(boxed_ty, interned_ty);
+
+        let boxed = measure(|| {
+            InferCtxt::new().infer(&expr).unwrap();
+        });
+        let interned = measure(|| {
+            intern::infer::InferCtxt::new().infer(&expr).unwrap();
+        });
+        for (name, (time, allocs, bytes)) in [("boxed", boxed), ("interned", interned)] {
+            println!("{:>9} {:>8} {:>12?} {:>12} {:>14}", bindings, name, time, allocs, bytes);
+        }
+    }
+}
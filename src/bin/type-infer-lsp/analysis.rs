@@ -0,0 +1,121 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use type_infer_rs::{
+    error::ParseError,
+    expr::{ast::Ast, expr_id::ExprId},
+    infer::{InferCtxt, Recovered},
+    prelude,
+    resolve::{resolve, Resolution},
+    syntax::{parser::{parse, Parsed}, span::Span},
+    pretty::PrettyCtxt,
+    ty::Ty,
+};
+
+/// The result of checking one open document.
+pub struct Analysis {
+    pub text: String,
+    result: Result<(Parsed, Ast, Resolution, Recovered), ParseError>,
+}
+
+impl Analysis {
+    pub fn new(text: String) -> Analysis {
+        let result = parse(&text).map(|parsed| {
+            let recovered = InferCtxt::new().infer_recovering_in(prelude::tcx(), &parsed.expr);
+            let ast = Ast::from_parsed(&parsed);
+            let resolution = resolve(&parsed.expr);
+            (parsed, ast, resolution, recovered)
+        });
+        Analysis { text, result }
+    }
+
+    /// The errors of the document, as spans and messages.
+    pub fn diagnostics(&self) -> Vec<(Span, String)> {
+        match &self.result {
+            Err(err) => vec![(err.span, err.message.clone())],
+            Ok((parsed, _, _, recovered)) => recovered.errors.iter()
+                .map(|(id, err)| (parsed.spans[id], err.to_string()))
+                .collect(),
+        }
+    }
+
+    /// The type of the innermost node under the cursor. On the name bound by
+    /// a `let` it is the generalized type scheme of the name.
+    pub fn hover(&self, offset: usize) -> Option<(Span, String)> {
+        let (parsed, ast, _, recovered) = self.result.as_ref().ok()?;
+        if let Some((id, span)) = innermost(&parsed.binders, offset) {
+            if let Some(tys) = recovered.schemes.get(&id) {
+                return Some((span, PrettyCtxt::new().scheme(tys)));
+            }
+            // The parameter of a `λ`.
+            if let Some(Ty::Arrow(param_ty, _)) = recovered.types.get(&id) {
+                return Some((span, monotype(param_ty)));
+            }
+        }
+        let id = ast.enclosing(offset)?;
+        Some((ast.span(id)?, monotype(recovered.types.get(&id)?)))
+    }
+
+    /// The span of the name that the variable under the cursor refers to.
+    pub fn definition(&self, offset: usize) -> Option<Span> {
+        let (_, ast, resolution, _) = self.result.as_ref().ok()?;
+        let binder = resolution.bindings.get(&ast.enclosing(offset)?)?;
+        ast[*binder].binder_span
+    }
+
+    /// The type schemes of `let` bindings, shown after the bound names.
+    pub fn inlay_hints(&self) -> Vec<(usize, String)> {
+        match &self.result {
+            Ok((parsed, _, _, recovered)) => recovered.schemes.iter()
+                .map(|(id, tys)| {
+                    (parsed.binders[id].hi, format!(": {}", PrettyCtxt::new().scheme(tys)))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Converts a byte offset to an LSP position, a line and a UTF-16 column.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count();
+        (line, before[line_start..].encode_utf16().count())
+    }
+
+    /// Converts an LSP position to a byte offset, clamped to the text.
+    pub fn offset(&self, line: usize, character: usize) -> usize {
+        let line_start = match line {
+            0 => 0,
+            _ => match self.text.match_indices('\n').nth(line - 1) {
+                Some((i, _)) => i + 1,
+                None => return self.text.len(),
+            },
+        };
+        let mut units = 0;
+        for (i, c) in self.text[line_start..].char_indices() {
+            if units >= character || c == '\n' {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+        self.text.len()
+    }
+}
+
+/// Prints a type of a node. Its variables may be shared with other nodes, so
+/// they are not renamed.
+fn monotype(ty: &Ty) -> String {
+    PrettyCtxt::new().with_renaming(false).ty(ty)
+}
+
+/// Finds the shortest span that contains the offset.
+fn innermost(
+    spans: &std::collections::BTreeMap<ExprId, Span>,
+    offset: usize,
+) -> Option<(ExprId, Span)> {
+    spans.iter()
+        .filter(|(_, span)| span.contains(offset))
+        .min_by_key(|(_, span)| span.len())
+        .map(|(id, span)| (*id, *span))
+}
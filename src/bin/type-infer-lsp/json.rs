@@ -0,0 +1,277 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::fmt::{self, Write};
+
+/// A JSON value, just enough of it to speak JSON-RPC. Object members keep
+/// their order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(members: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(members.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn string(s: impl Into<String>) -> Json {
+        Json::String(s.into())
+    }
+
+    pub fn number(n: usize) -> Json {
+        Json::Number(n as f64)
+    }
+
+    /// Looks up a member of an object, `Null` if there is none.
+    pub fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(members) => members.iter()
+                .find(|(k, _)| k == key)
+                .map_or(&Json::Null, |(_, v)| v),
+            _ => &Json::Null,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Json::Null)
+    }
+
+    pub fn parse(src: &str) -> Result<Json, String> {
+        let mut parser = JsonParser { src, pos: 0 };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != src.len() {
+            return Err(format!("trailing characters at {}", parser.pos));
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 =>
+                write!(f, "{}", *n as i64),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            },
+            Json::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            },
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+struct JsonParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(format!("expected `{}` at {}", c, self.pos))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str, value: Json) -> Result<Json, String> {
+        if self.src[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            Ok(value)
+        } else {
+            Err(format!("unexpected token at {}", self.pos))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.eat_keyword("null", Json::Null),
+            Some('t') => self.eat_keyword("true", Json::Bool(true)),
+            Some('f') => self.eat_keyword("false", Json::Bool(false)),
+            Some('"') => self.parse_string().map(Json::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(items));
+                        },
+                        _ => return Err(format!("expected `,` or `]` at {}", self.pos)),
+                    }
+                }
+            },
+            Some('{') => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.parse_string()?;
+                    self.expect(':')?;
+                    members.push((key, self.parse_value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some('}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(members));
+                        },
+                        _ => return Err(format!("expected `,` or `}}` at {}", self.pos)),
+                    }
+                }
+            },
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let rest = &self.src[self.pos..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                    .unwrap_or(rest.len());
+                let n = rest[..len].parse()
+                    .map_err(|_| format!("invalid number at {}", self.pos))?;
+                self.pos += len;
+                Ok(Json::Number(n))
+            },
+            _ => Err(format!("unexpected token at {}", self.pos)),
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let hex = self.src.get(self.pos..self.pos + 4)
+            .ok_or_else(|| format!("invalid escape at {}", self.pos))?;
+        self.pos += 4;
+        u32::from_str_radix(hex, 16).map_err(|_| format!("invalid escape at {}", self.pos))
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            let c = self.peek().ok_or("unterminated string")?;
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(s),
+                '\\' => {
+                    let escape = self.peek().ok_or("unterminated string")?;
+                    self.pos += escape.len_utf8();
+                    match escape {
+                        '"' | '\\' | '/' => s.push(escape),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'u' => {
+                            let mut code = self.parse_hex4()?;
+                            // A high surrogate is followed by a low one. A
+                            // lone surrogate is not a character, it becomes
+                            // U+FFFD, and the escape after it is read on its own.
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.src[self.pos..].starts_with("\\u")
+                            {
+                                let pos = self.pos;
+                                self.pos += 2;
+                                match self.parse_hex4() {
+                                    Ok(low) if (0xdc00..0xe000).contains(&low) =>
+                                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00),
+                                    _ => self.pos = pos,
+                                }
+                            }
+                            s.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        },
+                        c => return Err(format!("invalid escape `\\{}` at {}", c, self.pos - c.len_utf8())),
+                    }
+                },
+                c => s.push(c),
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// A Language Server Protocol server speaking JSON-RPC over stdio.
+//
+// $ cargo run --bin type-infer-lsp
+
+mod analysis;
+mod json;
+
+use std::{collections::BTreeMap, io::{self, BufRead, Read, Write}, process};
+
+use type_infer_rs::syntax::span::Span;
+
+use self::{analysis::Analysis, json::Json};
+
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+/// Reads one message framed by a `Content-Length` header, `None` at the end
+/// of the input. A message that can't be read is skipped, and the reason is
+/// returned instead, so that the server can answer it with a parse error.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Result<Json, String>>> {
+    let mut content_length = None;
+    loop {
+        let mut line = Vec::new();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        // The header is looked for anywhere in the line, as the body of a
+        // skipped message may run into the header of the next one.
+        const HEADER: &str = "content-length:";
+        if let Some(at) = line.to_ascii_lowercase().rfind(HEADER) {
+            content_length = line[at + HEADER.len()..].trim().parse::<usize>().ok();
+        }
+    }
+    let Some(len) = content_length else {
+        return Ok(Some(Err("missing Content-Length".to_string())));
+    };
+    let mut content = Vec::new();
+    input.take(len as u64).read_to_end(&mut content)?;
+    if content.len() < len {
+        return Ok(Some(Err(format!("expected {} bytes of content, found {}", len, content.len()))));
+    }
+    let message = String::from_utf8(content)
+        .map_err(|err| err.to_string())
+        .and_then(|content| Json::parse(&content));
+    Ok(Some(message))
+}
+
+fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let content = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    output.flush()
+}
+
+struct Server {
+    documents: BTreeMap<String, Analysis>,
+    shutdown: bool,
+    exit: bool,
+    outgoing: Vec<Json>,
+}
+
+impl Server {
+    fn new() -> Server {
+        Server {
+            documents: BTreeMap::new(),
+            shutdown: false,
+            exit: false,
+            outgoing: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, message: &Json) {
+        // Responses to requests we never sent have no method, they are
+        // ignored, and so are the notifications we don't know.
+        let Some(method) = message.get("method").as_str() else {
+            return;
+        };
+        let params = message.get("params");
+        let id = message.get("id");
+        if id.is_null() {
+            self.notification(method, params);
+            return;
+        }
+        let result = match method {
+            "initialize" => Ok(Json::object([
+                ("capabilities", Json::object([
+                    ("textDocumentSync", Json::number(1)),
+                    ("hoverProvider", Json::Bool(true)),
+                    ("definitionProvider", Json::Bool(true)),
+                    ("inlayHintProvider", Json::Bool(true)),
+                ])),
+                ("serverInfo", Json::object([
+                    ("name", Json::string("type-infer-lsp")),
+                ])),
+            ])),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            },
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/inlayHint" => self.inlay_hints(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+        };
+        let response = match result {
+            Ok(result) => Json::object([
+                ("jsonrpc", Json::string("2.0")),
+                ("id", id.clone()),
+                ("result", result),
+            ]),
+            Err((code, message)) => error_response(id.clone(), code, message),
+        };
+        self.outgoing.push(response);
+    }
+
+    /// Answers a message that could not be read. Its id is unknown, so the
+    /// response has a null id.
+    fn parse_error(&mut self, message: String) {
+        self.outgoing.push(error_response(Json::Null, PARSE_ERROR, message));
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) {
+        let uri = params.get("textDocument").get("uri").as_str().map(str::to_string);
+        match (method, uri) {
+            ("exit", _) => self.exit = true,
+            ("textDocument/didOpen", Some(uri)) => {
+                let text = params.get("textDocument").get("text").as_str().unwrap_or_default();
+                self.update(uri, text.to_string());
+            },
+            ("textDocument/didChange", Some(uri)) => {
+                // We only ask for full document synchronization, so the last
+                // change holds the whole text.
+                let changes = params.get("contentChanges").as_array().unwrap_or_default();
+                if let Some(text) = changes.last().and_then(|c| c.get("text").as_str()) {
+                    self.update(uri, text.to_string());
+                }
+            },
+            ("textDocument/didClose", Some(uri)) => {
+                self.documents.remove(&uri);
+                self.publish_diagnostics(&uri, Json::Array(Vec::new()));
+            },
+            _ => {},
+        }
+    }
+
+    fn update(&mut self, uri: String, text: String) {
+        let analysis = Analysis::new(text);
+        let diagnostics = analysis.diagnostics().into_iter()
+            .map(|(span, message)| Json::object([
+                ("range", range(&analysis, span)),
+                ("severity", Json::number(1)),
+                ("source", Json::string("type-infer")),
+                ("message", Json::string(message)),
+            ]))
+            .collect();
+        self.documents.insert(uri.clone(), analysis);
+        self.publish_diagnostics(&uri, Json::Array(diagnostics));
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Json) {
+        self.outgoing.push(Json::object([
+            ("jsonrpc", Json::string("2.0")),
+            ("method", Json::string("textDocument/publishDiagnostics")),
+            ("params", Json::object([
+                ("uri", Json::string(uri)),
+                ("diagnostics", diagnostics),
+            ])),
+        ]));
+    }
+
+    /// Finds the document and the cursor offset of a text document position
+    /// request.
+    fn locate<'a>(&self, params: &'a Json) -> Result<(&'a str, &Analysis, usize), (i32, String)> {
+        let uri = params.get("textDocument").get("uri").as_str()
+            .ok_or((INVALID_PARAMS, "missing text document".to_string()))?;
+        let analysis = self.documents.get(uri)
+            .ok_or((INVALID_PARAMS, format!("unknown document {}", uri)))?;
+        let position = params.get("position");
+        let offset = match (position.get("line").as_usize(), position.get("character").as_usize()) {
+            (Some(line), Some(character)) => analysis.offset(line, character),
+            _ => 0,
+        };
+        Ok((uri, analysis, offset))
+    }
+
+    fn hover(&self, params: &Json) -> Result<Json, (i32, String)> {
+        let (_, analysis, offset) = self.locate(params)?;
+        Ok(match analysis.hover(offset) {
+            Some((span, ty)) => Json::object([
+                ("contents", Json::object([
+                    ("kind", Json::string("markdown")),
+                    ("value", Json::string(format!("```\n{}\n```", ty))),
+                ])),
+                ("range", range(analysis, span)),
+            ]),
+            None => Json::Null,
+        })
+    }
+
+    fn definition(&self, params: &Json) -> Result<Json, (i32, String)> {
+        let (uri, analysis, offset) = self.locate(params)?;
+        Ok(match analysis.definition(offset) {
+            Some(span) => Json::object([
+                ("uri", Json::string(uri)),
+                ("range", range(analysis, span)),
+            ]),
+            None => Json::Null,
+        })
+    }
+
+    fn inlay_hints(&self, params: &Json) -> Result<Json, (i32, String)> {
+        let (_, analysis, _) = self.locate(params)?;
+        let hints = analysis.inlay_hints().into_iter()
+            .map(|(offset, label)| Json::object([
+                ("position", position(analysis, offset)),
+                ("label", Json::string(label)),
+                ("kind", Json::number(1)),
+            ]))
+            .collect();
+        Ok(Json::Array(hints))
+    }
+}
+
+fn error_response(id: Json, code: i32, message: String) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("id", id),
+        ("error", Json::object([
+            ("code", Json::Number(code as f64)),
+            ("message", Json::string(message)),
+        ])),
+    ])
+}
+
+fn position(analysis: &Analysis, offset: usize) -> Json {
+    let (line, character) = analysis.position(offset);
+    Json::object([
+        ("line", Json::number(line)),
+        ("character", Json::number(character)),
+    ])
+}
+
+fn range(analysis: &Analysis, span: Span) -> Json {
+    Json::object([
+        ("start", position(analysis, span.lo)),
+        ("end", position(analysis, span.hi)),
+    ])
+}
+
+fn main() -> io::Result<()> {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    let mut server = Server::new();
+
+    while let Some(message) = read_message(&mut input)? {
+        match message {
+            Ok(message) => server.handle(&message),
+            Err(err) => server.parse_error(err),
+        }
+        for outgoing in server.outgoing.drain(..) {
+            write_message(&mut output, &outgoing)?;
+        }
+        if server.exit {
+            break;
+        }
+    }
+
+    // The exit code tells whether the client asked for a shutdown first.
+    process::exit(if server.shutdown { 0 } else { 1 })
+}
@@ -0,0 +1,290 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    expr::{expr::{Expr, ExprKind, Ident}, lit::Lit},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, types::Types, subst::Subst},
+};
+
+/// The inference rule applied at a node of a derivation tree, named after the
+/// labels in the comments of `InferCtxt::infer_impl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Var,
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    Unit,
+    Abs,
+    App,
+    Let,
+    Hole,
+}
+
+impl Rule {
+    fn of(expr: &Expr) -> Rule {
+        match expr.kind {
+            ExprKind::Var(_) => Rule::Var,
+            ExprKind::Lit(Lit::Int(_)) => Rule::Int,
+            ExprKind::Lit(Lit::Float(_)) => Rule::Float,
+            ExprKind::Lit(Lit::Bool(_)) => Rule::Bool,
+            ExprKind::Lit(Lit::Char(_)) => Rule::Char,
+            ExprKind::Lit(Lit::String(_)) => Rule::String,
+            ExprKind::Lit(Lit::Unit) => Rule::Unit,
+            ExprKind::Abs { .. } => Rule::Abs,
+            ExprKind::App { .. } => Rule::App,
+            ExprKind::Let { .. } => Rule::Let,
+            ExprKind::Hole(_) => Rule::Hole,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Var => write!(f, "Var"),
+            Rule::Int => write!(f, "Int"),
+            Rule::Float => write!(f, "Float"),
+            Rule::Bool => write!(f, "Bool"),
+            Rule::Char => write!(f, "Char"),
+            Rule::String => write!(f, "String"),
+            Rule::Unit => write!(f, "Unit"),
+            Rule::Abs => write!(f, "Abs"),
+            Rule::App => write!(f, "App"),
+            Rule::Let => write!(f, "Let"),
+            Rule::Hole => write!(f, "Hole"),
+        }
+    }
+}
+
+/// A typing derivation, one node per rule application with the judgment
+/// `Γ ⊢ e : τ` it concludes.
+///
+/// The premises of a `Let` node are the derivations of the bound value and of
+/// the body, the generalization step in between is left implicit, the same
+/// way it is in Algorithm W.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, infer::InferCtxt};
+/// // (λ x. x) 42
+/// let expr = Expr::mk_app(
+///     Expr::mk_abs(
+///         Ident::new("x".to_string()),
+///         Expr::mk_var(Ident::new("x".to_string())),
+///     ),
+///     Expr::mk_lit(Lit::Int(42)),
+/// ).assign_ids();
+///
+/// let derivation = InferCtxt::new().derive(&expr).unwrap();
+/// assert_eq!(derivation.to_text(), "\
+/// (App) ⊢ (λ x. x 42) : int
+///   (Abs) ⊢ λ x. x : int -> int
+///     (Var) x : int ⊢ x : int
+///   (Int) ⊢ 42 : int
+/// ");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Derivation {
+    pub rule: Rule,
+    pub tcx: TyCtxt,
+    pub expr: Expr,
+    pub ty: Ty,
+    pub premises: Vec<Derivation>,
+}
+
+impl Derivation {
+    pub fn new(tcx: TyCtxt, expr: Expr, ty: Ty, premises: Vec<Derivation>) -> Derivation {
+        Derivation { rule: Rule::of(&expr), tcx, expr, ty, premises }
+    }
+
+    /// Applies a substitution to every judgment in the tree. Algorithm W only
+    /// knows the final type of a node after the whole program is inferred, so
+    /// the tree is recorded first and resolved by the final substitution.
+    pub(crate) fn apply(&self, subst: &Subst) -> Derivation {
+        Derivation {
+            rule: self.rule,
+            tcx: self.tcx.apply(subst),
+            expr: self.expr.clone(),
+            ty: self.ty.apply(subst),
+            premises: self.premises.iter().map(|p| p.apply(subst)).collect(),
+        }
+    }
+
+    /// Renders the tree as indented plain text, conclusions first and each
+    /// premise indented by two spaces under the rule it belongs to.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0);
+        out
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let tcx = text_tcx(&self.tcx);
+        let _ = writeln!(
+            out, "{:indent$}({}) {}⊢ {} : {}",
+            "", self.rule,
+            if tcx.is_empty() { tcx } else { tcx + " " },
+            self.expr, self.ty,
+            indent = depth * 2,
+        );
+        for premise in &self.premises {
+            premise.write_text(out, depth + 1);
+        }
+    }
+
+    /// Renders the tree as a `prooftree` environment of the LaTeX package
+    /// `bussproofs`.
+    pub fn to_latex(&self) -> String {
+        let mut out = String::from("\\begin{prooftree}\n");
+        self.write_latex(&mut out);
+        out.push_str("\\end{prooftree}\n");
+        out
+    }
+
+    fn write_latex(&self, out: &mut String) {
+        let premises = match (&self.rule, &self.expr.kind) {
+            // The side condition `x : σ ∈ Γ` is the only premise of (Var).
+            (Rule::Var, ExprKind::Var(name)) => {
+                let side = match self.tcx.get(name) {
+                    Some(tys) => format!(
+                        "{} : {} \\in \\Gamma",
+                        latex_ident(name), latex_scheme(tys),
+                    ),
+                    None => String::new(),
+                };
+                let _ = writeln!(out, "\\AxiomC{{${}$}}", side);
+                1
+            },
+            _ if self.premises.is_empty() => {
+                out.push_str("\\AxiomC{}\n");
+                1
+            },
+            _ => {
+                for premise in &self.premises {
+                    premise.write_latex(out);
+                }
+                self.premises.len()
+            },
+        };
+        let inference = match premises {
+            1 => "UnaryInfC",
+            2 => "BinaryInfC",
+            3 => "TrinaryInfC",
+            4 => "QuaternaryInfC",
+            _ => "QuinaryInfC",
+        };
+        let _ = writeln!(out, "\\RightLabel{{\\scriptsize({})}}", self.rule);
+        let _ = writeln!(
+            out, "\\{}{{${} \\vdash {} : {}$}}",
+            inference, latex_tcx(&self.tcx), latex_expr(&self.expr),
+            latex_ty(&self.ty),
+        );
+    }
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+fn text_tcx(tcx: &TyCtxt) -> String {
+    tcx.iter()
+        .map(|(name, tys)| format!("{} : {}", name, tys))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Escapes the characters that are special in LaTeX.
+fn latex_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '$' | '{' | '}' | '%' | '#' | '&' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn latex_ident(ident: &Ident) -> String {
+    format!("\\mathit{{{}}}", latex_escape(&ident.name))
+}
+
+fn latex_var(var: &TyVar) -> String {
+    match var {
+        TyVar::Name(name) => latex_escape(name),
+        TyVar::Unknown(id) | TyVar::Num(id) => format!("?_{{{}}}", id),
+    }
+}
+
+fn latex_ty(ty: &Ty) -> String {
+    match ty {
+        Ty::Var(var) => latex_var(var),
+        Ty::Int => "\\mathsf{int}".to_string(),
+        Ty::Float => "\\mathsf{float}".to_string(),
+        Ty::Bool => "\\mathsf{bool}".to_string(),
+        Ty::Char => "\\mathsf{char}".to_string(),
+        Ty::String => "\\mathsf{string}".to_string(),
+        Ty::Unit => "\\mathsf{unit}".to_string(),
+        Ty::Error => "\\bot".to_string(),
+        Ty::Arrow(param_ty, ret_ty) => match **param_ty {
+            Ty::Arrow(_, _) => format!(
+                "({}) \\to {}", latex_ty(param_ty), latex_ty(ret_ty),
+            ),
+            _ => format!("{} \\to {}", latex_ty(param_ty), latex_ty(ret_ty)),
+        },
+    }
+}
+
+fn latex_scheme(tys: &TyScheme) -> String {
+    if tys.vars.is_empty() {
+        latex_ty(&tys.ty)
+    } else {
+        format!(
+            "\\forall {} .\\, {}",
+            tys.vars.iter().map(latex_var).collect::<Vec<_>>().join("\\, "),
+            latex_ty(&tys.ty),
+        )
+    }
+}
+
+fn latex_tcx(tcx: &TyCtxt) -> String {
+    let entries = tcx.iter()
+        .map(|(name, tys)| format!("{} : {}", latex_ident(name), latex_scheme(tys)))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        "\\cdot".to_string()
+    } else {
+        entries.join(", ")
+    }
+}
+
+fn latex_expr(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Var(ident) => latex_ident(ident),
+        ExprKind::Lit(lit) => format!("\\texttt{{{}}}", latex_escape(&lit.to_string())),
+        ExprKind::App { callee, arg } =>
+            format!("({}\\ {})", latex_expr(callee), latex_expr(arg)),
+        ExprKind::Abs { param, body } =>
+            format!("\\lambda {} .\\, {}", latex_ident(param), latex_expr(body)),
+        ExprKind::Let { name, value, body } => format!(
+            "\\mathsf{{let}}\\ {} = {}\\ \\mathsf{{in}}\\ {}",
+            latex_ident(name), latex_expr(value), latex_expr(body),
+        ),
+        ExprKind::Hole(Some(name)) => format!("\\_{}", latex_ident(name)),
+        ExprKind::Hole(None) => "\\_".to_string(),
+    }
+}
@@ -1,13 +1,316 @@
 // Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
 // root for license information.
 
+use std::fmt;
+
+use crate::{eval::{Prim, Value}, expr::expr::Ident, syntax::span::Span, system_f::FTy, ty::{Ty, TyVar, ty_scheme::TyScheme}};
+
+/// A type error, with optional notes that help to fix it.
 #[derive(Debug, Clone, PartialEq)]
-pub enum TyError {
-    // FIXME: Remove `Unknown` error later.
+pub struct TyError {
+    pub kind: TyErrorKind,
+    pub notes: Vec<Note>,
+}
+
+impl TyError {
+    pub fn new(kind: TyErrorKind) -> TyError {
+        TyError { kind, notes: Vec::new() }
+    }
+
+    pub fn mk_unbound_var(name: Ident) -> TyError {
+        TyError::new(TyErrorKind::UnboundVar(name))
+    }
+
+    pub fn mk_mismatch(ty1: Ty, ty2: Ty) -> TyError {
+        TyError::new(TyErrorKind::Mismatch(ty1, ty2))
+    }
+
+    pub fn mk_infinite_type(var: TyVar, ty: Ty) -> TyError {
+        TyError::new(TyErrorKind::InfiniteType(var, ty))
+    }
+
+    /// Attaches a note to the error.
+    pub fn with_note(mut self, note: Note) -> TyError {
+        self.notes.push(note);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TyErrorKind {
+    /// A variable is not bound in the type context.
+    UnboundVar(Ident),
+
+    /// Two types cannot be unified.
+    Mismatch(Ty, Ty),
+
+    /// A type variable would have to be bound to a type containing itself,
+    /// the occurs check fails.
+    InfiniteType(TyVar, Ty),
+}
+
+/// A hint about what might have caused a type error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Note {
+    /// A variable with a similar name is in scope.
+    DidYouMean(Ident),
+
+    /// The argument is a function with type `found`, but only its result
+    /// type would fit the `expected` parameter type.
+    MissingArgument { found: Ty, expected: Ty },
+
+    /// The arguments fit the function if the two of them are swapped.
+    SwappedArguments,
+
+    /// The result of an application has the given non-function type, but it
+    /// is applied to one more argument.
+    ExtraArgument(Ty),
 
-    /// Represents an ad-hoc error message.
-    Unknown(String),
+    /// The callee has the given non-function type.
+    NotAFunction(Ty),
+}
+
+impl fmt::Display for TyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            TyErrorKind::UnboundVar(name) =>
+                write!(f, "unbound variable {name}")?,
+            TyErrorKind::Mismatch(ty1, ty2) =>
+                write!(f, "cannot unify {ty1} with {ty2}")?,
+            TyErrorKind::InfiniteType(var, ty) =>
+                write!(f, "occur check fails {var} in {ty}")?,
+        }
+        for note in &self.notes {
+            write!(f, "\nnote: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Note::DidYouMean(name) =>
+                write!(f, "did you mean `{name}`?"),
+            Note::MissingArgument { found, expected } =>
+                write!(f, "found `{found}`, expected `{expected}`, \
+                    is an argument missing?"),
+            Note::SwappedArguments =>
+                write!(f, "the arguments would fit if they were swapped"),
+            Note::ExtraArgument(ty) =>
+                write!(f, "the callee has type `{ty}`, \
+                    it is applied to too many arguments"),
+            Note::NotAFunction(ty) =>
+                write!(f, "the callee has type `{ty}`, it is not a function"),
+        }
+    }
 }
 
 /// Type inference result for reporting type errors.
 pub type TyResult<T> = Result<T, TyError>;
+
+/// A warning about a program that type checks, but maybe not as intended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// The type of an integer literal is the numeric type variable, which
+    /// nothing decides, so it defaults to `int`.
+    Defaulted(TyVar),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::Defaulted(var) =>
+                write!(f, "the type {var} of the literal is ambiguous, it defaults to int"),
+        }
+    }
+}
+
+/// A syntax error at the given location of the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Span,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(span: Span, message: String) -> ParseError {
+        ParseError { span, message }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.span, self.message)
+    }
+}
+
+/// Parsing result for reporting syntax errors.
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// An error at run time. A well-typed program only ever fails by reaching a
+/// typed hole.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    UnboundVar(Ident),
+
+    /// A value that is not a closure is applied to an argument.
+    NotAFunction(Value),
+
+    /// Evaluation reached a typed hole.
+    Hole(Option<Ident>),
+
+    /// A primitive is applied to arguments of the wrong types.
+    BadArguments(Prim),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::UnboundVar(name) =>
+                write!(f, "unbound variable {name}"),
+            EvalError::NotAFunction(value) =>
+                write!(f, "cannot apply {value}, it is not a function"),
+            EvalError::Hole(Some(name)) =>
+                write!(f, "reached hole _{name}"),
+            EvalError::Hole(None) =>
+                write!(f, "reached hole _"),
+            EvalError::BadArguments(prim) =>
+                write!(f, "primitive {prim} is applied to arguments of the wrong types"),
+        }
+    }
+}
+
+/// Evaluation result for reporting run time errors.
+pub type EvalResult<T> = Result<T, EvalError>;
+
+/// An error of the System F type checker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemFError {
+    UnboundVar(Ident),
+    UnboundTyVar(TyVar),
+
+    /// The expected type and the type found are not alpha-equivalent.
+    Mismatch(FTy, FTy),
+
+    /// A term that is not a function is applied to an argument.
+    NotAFunction(FTy),
+
+    /// A term that is not a type abstraction is applied to a type.
+    NotPolymorphic(FTy),
+}
+
+impl fmt::Display for SystemFError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SystemFError::UnboundVar(name) =>
+                write!(f, "unbound variable {name}"),
+            SystemFError::UnboundTyVar(var) =>
+                write!(f, "unbound type variable {var}"),
+            SystemFError::Mismatch(expected, found) =>
+                write!(f, "expected {expected}, found {found}"),
+            SystemFError::NotAFunction(ty) =>
+                write!(f, "cannot apply a term of type {ty}"),
+            SystemFError::NotPolymorphic(ty) =>
+                write!(f, "cannot apply a term of type {ty} to a type"),
+        }
+    }
+}
+
+/// System F type checking result.
+pub type SystemFResult<T> = Result<T, SystemFError>;
+
+/// An error of checking a module against the modules it imports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleError {
+    /// An imported module doesn't exist.
+    UnknownModule(Ident),
+
+    /// The modules import each other in a cycle, starting and ending with
+    /// the same module.
+    ImportCycle(Vec<Ident>),
+
+    /// An imported module has no signature and failed to check, so there is
+    /// nothing to check against.
+    DependencyFailed(Ident),
+
+    /// A binding of the module fails to type check.
+    Binding(Ident, TyError),
+
+    /// A name in the signature is not bound by the module.
+    MissingBinding(Ident),
+
+    /// The inferred scheme of a binding is not as general as the scheme in
+    /// the signature.
+    NotGeneral { name: Ident, declared: TyScheme, inferred: TyScheme },
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModuleError::UnknownModule(name) =>
+                write!(f, "unknown module {name}"),
+            ModuleError::ImportCycle(names) => {
+                let names = names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+                write!(f, "modules import each other in a cycle: {}", names.join(" -> "))
+            },
+            ModuleError::DependencyFailed(name) =>
+                write!(f, "imported module {name} failed to check"),
+            ModuleError::Binding(name, err) =>
+                write!(f, "in {name}: {err}"),
+            ModuleError::MissingBinding(name) =>
+                write!(f, "{name} is declared in the signature but not bound"),
+            ModuleError::NotGeneral { name, declared, inferred } =>
+                write!(f, "{name} is declared as {declared}, \
+                    but its inferred type {inferred} is not as general"),
+        }
+    }
+}
+
+/// Module checking result.
+pub type ModuleResult<T> = Result<T, ModuleError>;
+
+/// An error of loading an interface file.
+#[derive(Debug)]
+pub enum InterfaceError {
+    Io(std::io::Error),
+
+    /// The file is not a valid interface.
+    Parse(ParseError),
+
+    /// The file was written in another version of the format.
+    Version(u32),
+
+    /// The source of the module changed since the file was written.
+    Stale(Ident),
+}
+
+impl fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterfaceError::Io(err) =>
+                write!(f, "{err}"),
+            InterfaceError::Parse(err) =>
+                write!(f, "invalid interface file: {err}"),
+            InterfaceError::Version(version) =>
+                write!(f, "interface file of unsupported version {version}"),
+            InterfaceError::Stale(name) =>
+                write!(f, "interface file of module {name} is out of date"),
+        }
+    }
+}
+
+impl From<std::io::Error> for InterfaceError {
+    fn from(err: std::io::Error) -> InterfaceError {
+        InterfaceError::Io(err)
+    }
+}
+
+impl From<ParseError> for InterfaceError {
+    fn from(err: ParseError) -> InterfaceError {
+        InterfaceError::Parse(err)
+    }
+}
+
+/// Interface loading result.
+pub type InterfaceResult<T> = Result<T, InterfaceError>;
@@ -0,0 +1,222 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use crate::{
+    error::{EvalError, EvalResult},
+    expr::{expr::{Expr, ExprKind, Ident}, lit::Lit},
+    ty::Ty,
+};
+
+/// A run time value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    String(String),
+    Unit,
+
+    /// A function value, `λ param. body` together with the environment it
+    /// was created in.
+    Closure { param: Ident, body: Arc<Expr>, env: Env },
+
+    /// A primitive function applied to fewer arguments than it takes.
+    Prim { prim: Prim, args: Vec<Value> },
+}
+
+impl From<&Lit> for Value {
+    fn from(lit: &Lit) -> Value {
+        match lit {
+            Lit::Int(int) => Value::Int(*int),
+            Lit::Float(float) => Value::Float(*float),
+            Lit::Bool(bool) => Value::Bool(*bool),
+            Lit::Char(char) => Value::Char(*char),
+            Lit::String(string) => Value::String(string.clone()),
+            Lit::Unit => Value::Unit,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(int) => write!(f, "{}", Lit::Int(*int)),
+            Value::Float(float) => write!(f, "{}", Lit::Float(*float)),
+            Value::Bool(bool) => write!(f, "{}", Lit::Bool(*bool)),
+            Value::Char(char) => write!(f, "{}", Lit::Char(*char)),
+            Value::String(string) => write!(f, "{:?}", string),
+            Value::Unit => write!(f, "{}", Lit::Unit),
+            Value::Closure { param, .. } => write!(f, "<closure λ {}>", param),
+            Value::Prim { prim, .. } => write!(f, "<primitive {}>", prim),
+        }
+    }
+}
+
+/// A primitive function of the [`crate::prelude`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prim {
+    /// `concat : string -> string -> string`
+    Concat,
+    /// `fadd : float -> float -> float`
+    FAdd,
+    /// `fsub : float -> float -> float`
+    FSub,
+    /// `fmul : float -> float -> float`
+    FMul,
+    /// `fdiv : float -> float -> float`
+    FDiv,
+}
+
+impl Prim {
+    pub const ALL: [Prim; 5] = [Prim::Concat, Prim::FAdd, Prim::FSub, Prim::FMul, Prim::FDiv];
+
+    pub fn name(self) -> Ident {
+        let name = match self {
+            Prim::Concat => "concat",
+            Prim::FAdd => "fadd",
+            Prim::FSub => "fsub",
+            Prim::FMul => "fmul",
+            Prim::FDiv => "fdiv",
+        };
+        Ident::new(name.to_string())
+    }
+
+    pub fn ty(self) -> Ty {
+        let (param_tys, ret_ty) = match self {
+            Prim::Concat => (vec![Ty::mk_string(), Ty::mk_string()], Ty::mk_string()),
+            Prim::FAdd | Prim::FSub | Prim::FMul | Prim::FDiv =>
+                (vec![Ty::mk_float(), Ty::mk_float()], Ty::mk_float()),
+        };
+        param_tys.into_iter().rev().fold(ret_ty, |ty, param_ty| Ty::mk_arrow(param_ty, ty))
+    }
+
+    /// The number of arguments the primitive takes, all of them are binary
+    /// so far.
+    pub fn arity(self) -> usize {
+        2
+    }
+
+    /// Calls the primitive with all of its arguments.
+    fn call(self, args: Vec<Value>) -> EvalResult<Value> {
+        match (self, <[Value; 2]>::try_from(args)) {
+            (Prim::Concat, Ok([Value::String(s1), Value::String(s2)])) => Ok(Value::String(s1 + &s2)),
+            (Prim::FAdd, Ok([Value::Float(x), Value::Float(y)])) => Ok(Value::Float(x + y)),
+            (Prim::FSub, Ok([Value::Float(x), Value::Float(y)])) => Ok(Value::Float(x - y)),
+            (Prim::FMul, Ok([Value::Float(x), Value::Float(y)])) => Ok(Value::Float(x * y)),
+            (Prim::FDiv, Ok([Value::Float(x), Value::Float(y)])) => Ok(Value::Float(x / y)),
+            _ => Err(EvalError::BadArguments(self)),
+        }
+    }
+}
+
+impl fmt::Display for Prim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The values of the variables in scope. Every closure holds the
+/// environment it was created in, so the values are shared between clones
+/// of an environment, and only copied when a clone is changed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Env {
+    values: Arc<BTreeMap<Ident, Value>>,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env { values: Arc::new(BTreeMap::new()) }
+    }
+
+    pub fn insert(&mut self, var: Ident, value: Value) {
+        Arc::make_mut(&mut self.values).insert(var, value);
+    }
+
+    pub fn get(&self, var: &Ident) -> Option<&Value> {
+        self.values.get(var)
+    }
+}
+
+/// Evaluates a closed expression, call-by-value.
+///
+/// Evaluation has no types, so an integer literal always evaluates to an
+/// integer. The literals inferred as `float` must be resolved with
+/// [`crate::infer::Recovered::resolve_lits`] first.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, eval::{eval, Value}};
+/// // let k = λ x. λ y. x in k 1 true
+/// let expr = Expr::mk_let(
+///     Ident::new("k".to_string()),
+///     Expr::mk_abs(
+///         Ident::new("x".to_string()),
+///         Expr::mk_abs(
+///             Ident::new("y".to_string()),
+///             Expr::mk_var(Ident::new("x".to_string())),
+///         ),
+///     ),
+///     Expr::mk_app(
+///         Expr::mk_app(
+///             Expr::mk_var(Ident::new("k".to_string())),
+///             Expr::mk_lit(Lit::Int(1)),
+///         ),
+///         Expr::mk_lit(Lit::Bool(true)),
+///     ),
+/// );
+///
+/// assert_eq!(eval(&expr), Ok(Value::Int(1)));
+/// ```
+pub fn eval(expr: &Expr) -> EvalResult<Value> {
+    eval_in(&Env::new(), expr)
+}
+
+/// Evaluates an expression in an environment, call-by-value.
+pub fn eval_in(env: &Env, expr: &Expr) -> EvalResult<Value> {
+    match &expr.kind {
+        ExprKind::Var(name) => env.get(name)
+            .cloned()
+            .ok_or_else(|| EvalError::UnboundVar(name.clone())),
+        ExprKind::Lit(lit) => Ok(Value::from(lit)),
+        ExprKind::Abs { param, body } => Ok(Value::Closure {
+            param: param.clone(),
+            body: Arc::new((**body).clone()),
+            env: env.clone(),
+        }),
+        ExprKind::App { callee, arg } => {
+            let callee = eval_in(env, callee)?;
+            let arg = eval_in(env, arg)?;
+            apply(callee, arg)
+        },
+        ExprKind::Let { name, value, body } => {
+            let value = eval_in(env, value)?;
+            let mut new_env = env.clone();
+            new_env.insert(name.clone(), value);
+            eval_in(&new_env, body)
+        },
+        ExprKind::Hole(name) => Err(EvalError::Hole(name.clone())),
+    }
+}
+
+/// Applies a function value to an argument.
+pub fn apply(callee: Value, arg: Value) -> EvalResult<Value> {
+    match callee {
+        Value::Closure { param, body, mut env } => {
+            env.insert(param, arg);
+            eval_in(&env, &body)
+        },
+        Value::Prim { prim, mut args } => {
+            args.push(arg);
+            if args.len() < prim.arity() {
+                Ok(Value::Prim { prim, args })
+            } else {
+                prim.call(args)
+            }
+        },
+        callee => Err(EvalError::NotAFunction(callee)),
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! An arena of expression nodes indexed by [`ExprId`]. Children are ids
+//! rather than boxes, and every node knows its parent, so a node can be found
+//! from its id in constant time and the tree can be walked in both ways.
+
+use std::ops::Index;
+
+use super::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit};
+use crate::syntax::{parser::Parsed, span::Span};
+
+/// A node of an [`Ast`], its children are ids in the same arena.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Var(Ident),
+    Lit(Lit),
+    App { callee: ExprId, arg: ExprId },
+    Abs { param: Ident, body: ExprId },
+    Let { name: Ident, value: ExprId, body: ExprId },
+    Hole(Option<Ident>),
+}
+
+impl NodeKind {
+    /// The ids of the children, from left to right.
+    pub fn children(&self) -> impl Iterator<Item = ExprId> {
+        let children = match *self {
+            NodeKind::Var(_) | NodeKind::Lit(_) | NodeKind::Hole(_) => [None, None],
+            NodeKind::App { callee, arg } => [Some(callee), Some(arg)],
+            NodeKind::Abs { body, .. } => [Some(body), None],
+            NodeKind::Let { value, body, .. } => [Some(value), Some(body)],
+        };
+        children.into_iter().flatten()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: ExprId,
+    pub kind: NodeKind,
+
+    /// The parent node, `None` for the root.
+    pub parent: Option<ExprId>,
+
+    /// The source location of the node, if it was parsed.
+    pub span: Option<Span>,
+
+    /// The source location of the name bound by an `Abs` or `Let` node, if it
+    /// was parsed.
+    pub binder_span: Option<Span>,
+}
+
+/// An expression stored in a `Vec`, the node with id `n` is at index `n - 1`.
+/// The ids are the ones of the converted [`Expr`], so tables keyed by them,
+/// like the types of [`crate::infer::Recovered`], apply to the nodes as well.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{ast::{Ast, NodeKind}, expr::Ident}, syntax::{parser::parse, span::Span}};
+/// let parsed = parse("let id = λ x. x in id 42").unwrap();
+/// let ast = Ast::from_parsed(&parsed);
+///
+/// // The innermost node at offset 21 is `id` in `id 42`, `λ` takes two bytes.
+/// let id = ast.enclosing(21).unwrap();
+/// assert_eq!(ast[id].kind, NodeKind::Var(Ident::new("id".to_string())));
+/// assert_eq!(ast[id].span, Some(Span::new(20, 22)));
+///
+/// // Its ancestors are the application and the `let`.
+/// assert_eq!(ast.ancestors(id).collect::<Vec<_>>().last(), Some(&ast.root()));
+/// assert_eq!(ast.to_expr(ast.root()), parsed.expr);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ast {
+    nodes: Vec<Node>,
+    root: ExprId,
+}
+
+impl Ast {
+    /// Converts an expression whose ids are assigned, by
+    /// [`Expr::assign_ids`] or the parser, so they are `1..=n` for `n` nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ids are not assigned, or are not `1..=n`.
+    pub fn from_expr(expr: &Expr) -> Ast {
+        fn go(expr: &Expr, parent: Option<ExprId>, nodes: &mut Vec<Option<Node>>) {
+            let kind = match &expr.kind {
+                ExprKind::Var(name) => NodeKind::Var(name.clone()),
+                ExprKind::Lit(lit) => NodeKind::Lit(lit.clone()),
+                ExprKind::Hole(name) => NodeKind::Hole(name.clone()),
+                ExprKind::App { callee, arg } => {
+                    go(callee, Some(expr.id), nodes);
+                    go(arg, Some(expr.id), nodes);
+                    NodeKind::App { callee: callee.id, arg: arg.id }
+                },
+                ExprKind::Abs { param, body } => {
+                    go(body, Some(expr.id), nodes);
+                    NodeKind::Abs { param: param.clone(), body: body.id }
+                },
+                ExprKind::Let { name, value, body } => {
+                    go(value, Some(expr.id), nodes);
+                    go(body, Some(expr.id), nodes);
+                    NodeKind::Let { name: name.clone(), value: value.id, body: body.id }
+                },
+            };
+            assert_ne!(expr.id, ExprId::dummy(), "the ids of {} are not assigned", expr);
+            let index = expr.id.as_u32() as usize - 1;
+            if nodes.len() <= index {
+                nodes.resize(index + 1, None);
+            }
+            assert!(nodes[index].is_none(), "the id {} is used twice", expr.id.as_u32());
+            nodes[index] = Some(Node {
+                id: expr.id,
+                kind,
+                parent,
+                span: None,
+                binder_span: None,
+            });
+        }
+
+        let mut nodes = Vec::new();
+        go(expr, None, &mut nodes);
+        let nodes = nodes.into_iter()
+            .enumerate()
+            .map(|(i, node)| node.unwrap_or_else(|| panic!("the id {} is not used", i + 1)))
+            .collect();
+        Ast { nodes, root: expr.id }
+    }
+
+    /// Converts a parsed expression, with the spans of its nodes.
+    pub fn from_parsed(parsed: &Parsed) -> Ast {
+        let mut ast = Ast::from_expr(&parsed.expr);
+        for node in &mut ast.nodes {
+            node.span = parsed.spans.get(&node.id).copied();
+            node.binder_span = parsed.binders.get(&node.id).copied();
+        }
+        ast
+    }
+
+    pub fn root(&self) -> ExprId {
+        self.root
+    }
+
+    pub fn get(&self, id: ExprId) -> Option<&Node> {
+        self.nodes.get((id.as_u32() as usize).checked_sub(1)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// An expression has at least one node.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The nodes in the order of their ids.
+    pub fn iter(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter()
+    }
+
+    pub fn parent(&self, id: ExprId) -> Option<ExprId> {
+        self[id].parent
+    }
+
+    pub fn children(&self, id: ExprId) -> impl Iterator<Item = ExprId> {
+        self[id].kind.children()
+    }
+
+    /// The parent, the parent of the parent, ..., up to the root.
+    pub fn ancestors(&self, id: ExprId) -> impl Iterator<Item = ExprId> + '_ {
+        std::iter::successors(self.parent(id), |id| self.parent(*id))
+    }
+
+    pub fn span(&self, id: ExprId) -> Option<Span> {
+        self[id].span
+    }
+
+    /// Finds the innermost node whose span contains the offset, walking down
+    /// from the root. Where the spans of two children meet, the shorter one
+    /// is taken.
+    pub fn enclosing(&self, offset: usize) -> Option<ExprId> {
+        let contains = |id: ExprId| self.span(id).filter(|span| span.contains(offset));
+        contains(self.root)?;
+        let mut id = self.root;
+        while let Some(child) = self.children(id)
+            .filter_map(|child| Some((child, contains(child)?)))
+            .min_by_key(|(_, span)| span.len())
+            .map(|(child, _)| child)
+        {
+            id = child;
+        }
+        Some(id)
+    }
+
+    /// Finds the `Abs` or `Let` node that binds a variable, `None` if the
+    /// variable is unbound or the node is not a variable.
+    pub fn binder(&self, id: ExprId) -> Option<ExprId> {
+        let NodeKind::Var(name) = &self[id].kind else {
+            return None;
+        };
+        let mut child = id;
+        for ancestor in self.ancestors(id) {
+            match &self[ancestor].kind {
+                NodeKind::Abs { param, .. } if param == name => return Some(ancestor),
+                // The name is only bound in the body of a `let`.
+                NodeKind::Let { name: bound, body, .. } if bound == name && *body == child =>
+                    return Some(ancestor),
+                _ => {},
+            }
+            child = ancestor;
+        }
+        None
+    }
+
+    /// Converts a subtree back into an expression, with the same ids.
+    pub fn to_expr(&self, id: ExprId) -> Expr {
+        let kind = match &self[id].kind {
+            NodeKind::Var(name) => ExprKind::Var(name.clone()),
+            NodeKind::Lit(lit) => ExprKind::Lit(lit.clone()),
+            NodeKind::Hole(name) => ExprKind::Hole(name.clone()),
+            NodeKind::App { callee, arg } => ExprKind::App {
+                callee: Box::new(self.to_expr(*callee)),
+                arg: Box::new(self.to_expr(*arg)),
+            },
+            NodeKind::Abs { param, body } => ExprKind::Abs {
+                param: param.clone(),
+                body: Box::new(self.to_expr(*body)),
+            },
+            NodeKind::Let { name, value, body } => ExprKind::Let {
+                name: name.clone(),
+                value: Box::new(self.to_expr(*value)),
+                body: Box::new(self.to_expr(*body)),
+            },
+        };
+        Expr { kind, id }
+    }
+}
+
+impl Index<ExprId> for Ast {
+    type Output = Node;
+
+    fn index(&self, id: ExprId) -> &Node {
+        self.get(id).expect("the id is a node of the expression")
+    }
+}
@@ -3,11 +3,12 @@
 
 use std::fmt;
 
-use crate::expr::expr_id::ExprId;
+use crate::{expr::expr_id::ExprId, visit::visit_mut::{self, VisitMut}};
 
 use super::lit::Lit;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Expr {
     pub kind: ExprKind,
     pub id: ExprId,
@@ -51,40 +52,39 @@ impl Expr {
         })
     }
 
-    pub fn assign_ids(mut self) -> Expr {
-        let mut id_gen = 0;
-        self.assign_ids_rec(&mut id_gen);
-        self
+    pub fn mk_hole(name: Option<Ident>) -> Expr {
+        Expr::new(ExprKind::Hole(name))
     }
 
-    fn assign_ids_rec(&mut self, used_id_space: &mut u32) {
-        *used_id_space += 1;
-        self.id = ExprId::from_u32(*used_id_space);
-        match &mut self.kind {
-            ExprKind::Var(_) => {}
-            ExprKind::Lit(_) => {}
-            ExprKind::App { callee, arg } => {
-                callee.assign_ids_rec(used_id_space);
-                arg.assign_ids_rec(used_id_space);
-            }
-            ExprKind::Abs { param: _, body } => {
-                body.assign_ids_rec(used_id_space);
-            }
-            ExprKind::Let { name: _, value, body } => {
-                value.assign_ids_rec(used_id_space);
-                body.assign_ids_rec(used_id_space);
+    /// Numbers the nodes `1..=n` in pre-order.
+    pub fn assign_ids(mut self) -> Expr {
+        struct IdAssigner(u32);
+
+        impl VisitMut for IdAssigner {
+            fn visit_expr_mut(&mut self, expr: &mut Expr) {
+                self.0 += 1;
+                expr.id = ExprId::from_u32(self.0);
+                visit_mut::walk_expr(self, expr);
             }
         }
+
+        IdAssigner(0).visit_expr_mut(&mut self);
+        self
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExprKind {
     Var(Ident),
     Lit(Lit),
     App { callee: Box<Expr>, arg: Box<Expr> },
     Abs { param: Ident, body: Box<Expr> },
     Let { name: Ident, value: Box<Expr>, body: Box<Expr> },
+
+    /// A typed hole `_` or `_name`, a placeholder for an expression that is
+    /// not written yet. It has whatever type its context expects.
+    Hole(Option<Ident>),
 }
 
 impl fmt::Display for Expr {
@@ -100,11 +100,17 @@ impl fmt::Display for Expr {
                 write!(f, "λ {}. {}", param, body),
             ExprKind::Let { name, value, body } =>
                 write!(f, "let {} = {} in {}", name, value, body),
+            ExprKind::Hole(Some(name)) =>
+                write!(f, "_{}", name),
+            ExprKind::Hole(None) =>
+                write!(f, "_"),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Ident {
     pub name: String,
 }
@@ -2,6 +2,8 @@
 // root for license information.
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ExprId {
     private: u32,
 }
@@ -14,6 +16,10 @@ impl ExprId {
         ExprId { private: id }
     }
 
+    pub fn as_u32(self) -> u32 {
+        self.private
+    }
+
     pub fn dummy() -> ExprId {
         DUMMY_NODE_ID
     }
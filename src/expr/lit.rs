@@ -3,17 +3,56 @@
 
 use std::fmt;
 
+use crate::ty::Ty;
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Lit {
-    Int(i32),
+    Int(i64),
+    Float(f64),
     Bool(bool),
+    Char(char),
+    String(String),
+    Unit,
+}
+
+impl Lit {
+    /// The base type of the literal. Inference gives an integer literal a
+    /// numeric type variable instead, and `int` is the type it defaults to.
+    pub fn ty(&self) -> Ty {
+        match self {
+            Lit::Int(_) => Ty::mk_int(),
+            Lit::Float(_) => Ty::mk_float(),
+            Lit::Bool(_) => Ty::mk_bool(),
+            Lit::Char(_) => Ty::mk_char(),
+            Lit::String(_) => Ty::mk_string(),
+            Lit::Unit => Ty::mk_unit(),
+        }
+    }
+
+    /// The literal at the type inferred for it. An integer literal inferred
+    /// as `float` is the float of the same value, every other literal stays
+    /// as it is.
+    pub fn at_ty(&self, ty: &Ty) -> Lit {
+        match (self, ty) {
+            (Lit::Int(int), Ty::Float) => Lit::Float(*int as f64),
+            (lit, _) => lit.clone(),
+        }
+    }
 }
 
 impl fmt::Display for Lit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Lit::Int(int) => write!(f, "{}", int),
+            // The debug format always has a `.` or an exponent, so floats
+            // read back as floats. Only finite ones do, the parser has no
+            // literal for `inf` or `NaN`.
+            Lit::Float(float) => write!(f, "{:?}", float),
             Lit::Bool(bool) => write!(f, "{}", bool),
+            Lit::Char(char) => write!(f, "{:?}", char),
+            Lit::String(string) => write!(f, "{:?}", string),
+            Lit::Unit => write!(f, "()"),
         }
     }
 }
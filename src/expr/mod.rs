@@ -2,5 +2,8 @@
 // root for license information.
 
 pub mod lit;
+#[allow(clippy::module_inception)]
 pub mod expr;
 pub mod expr_id;
+pub mod ast;
+pub mod vars;
@@ -0,0 +1,287 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Variables of terms: free variables, substitution of expressions for
+//! variables, alpha-equivalence, and the variables captured by closures.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId};
+use crate::visit::{self, Visit};
+
+impl Expr {
+    /// The variables which are not bound in the expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{expr::expr::Ident, syntax::parser::parse};
+    /// let expr = parse("let f = λ x. x y in f z").unwrap().expr;
+    /// assert_eq!(
+    ///     expr.fv().into_iter().collect::<Vec<_>>(),
+    ///     [Ident::new("y".to_string()), Ident::new("z".to_string())],
+    /// );
+    /// ```
+    pub fn fv(&self) -> BTreeSet<Ident> {
+        struct FreeVars {
+            bound: Vec<Ident>,
+            fv: BTreeSet<Ident>,
+        }
+
+        impl Visit for FreeVars {
+            fn visit_expr(&mut self, expr: &Expr) {
+                match &expr.kind {
+                    ExprKind::Var(name) if !self.bound.contains(name) => {
+                        self.fv.insert(name.clone());
+                    },
+                    ExprKind::Abs { param, body } => {
+                        self.bound.push(param.clone());
+                        self.visit_expr(body);
+                        self.bound.pop();
+                    },
+                    ExprKind::Let { name, value, body } => {
+                        self.visit_expr(value);
+                        self.bound.push(name.clone());
+                        self.visit_expr(body);
+                        self.bound.pop();
+                    },
+                    _ => visit::walk_expr(self, expr),
+                }
+            }
+        }
+
+        let mut fv = FreeVars { bound: Vec::new(), fv: BTreeSet::new() };
+        fv.visit_expr(self);
+        fv.fv
+    }
+
+    /// Substitutes an expression for the free occurrences of a variable,
+    /// `self[var := expr]`. Binders that would capture the free variables of
+    /// the expression are renamed.
+    ///
+    /// Every occurrence is a copy of the expression with the same ids, so the
+    /// ids must be assigned again before inference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{expr::expr::{Expr, Ident}, syntax::parser::parse};
+    /// // (λ y. x y)[x := y] = λ y1. y y1
+    /// let expr = parse("λ y. x y").unwrap().expr;
+    /// let y = Expr::mk_var(Ident::new("y".to_string()));
+    /// assert_eq!(expr.subst(&Ident::new("x".to_string()), &y).to_string(), "λ y1. (y y1)");
+    /// ```
+    pub fn subst(&self, var: &Ident, expr: &Expr) -> Expr {
+        self.subst_impl(var, expr, &expr.fv())
+    }
+
+    fn subst_impl(&self, var: &Ident, expr: &Expr, expr_fv: &BTreeSet<Ident>) -> Expr {
+        let kind = match &self.kind {
+            ExprKind::Var(name) if name == var => return expr.clone(),
+            ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) => return self.clone(),
+            ExprKind::App { callee, arg } => ExprKind::App {
+                callee: Box::new(callee.subst_impl(var, expr, expr_fv)),
+                arg: Box::new(arg.subst_impl(var, expr, expr_fv)),
+            },
+            ExprKind::Abs { param, body } => {
+                let (param, body) = self.subst_binder(param, body, var, expr, expr_fv);
+                ExprKind::Abs { param, body: Box::new(body) }
+            },
+            ExprKind::Let { name, value, body } => {
+                let value = value.subst_impl(var, expr, expr_fv);
+                let (name, body) = self.subst_binder(name, body, var, expr, expr_fv);
+                ExprKind::Let { name, value: Box::new(value), body: Box::new(body) }
+            },
+        };
+        Expr { kind, id: self.id }
+    }
+
+    /// Substitutes in the scope of a binder, and returns the binder renamed
+    /// if it would capture a free variable of the expression.
+    fn subst_binder(
+        &self,
+        bound: &Ident,
+        body: &Expr,
+        var: &Ident,
+        expr: &Expr,
+        expr_fv: &BTreeSet<Ident>,
+    ) -> (Ident, Expr) {
+        if bound == var || !body.fv().contains(var) {
+            return (bound.clone(), body.clone());
+        }
+        if !expr_fv.contains(bound) {
+            return (bound.clone(), body.subst_impl(var, expr, expr_fv));
+        }
+        // The fresh name must not be captured in the body either, so it
+        // avoids the bound names of the body as well.
+        let mut avoid = body.names();
+        avoid.extend(expr_fv.iter().cloned());
+        let fresh = (1..)
+            .map(|i| Ident::new(format!("{}{}", bound.name, i)))
+            .find(|fresh| !avoid.contains(fresh))
+            .expect("there are infinitely many names");
+        let body = body.rename_free(bound, &fresh);
+        (fresh, body.subst_impl(var, expr, expr_fv))
+    }
+
+    /// All names in the expression, bound or free.
+    fn names(&self) -> BTreeSet<Ident> {
+        struct Names(BTreeSet<Ident>);
+
+        impl Visit for Names {
+            fn visit_expr(&mut self, expr: &Expr) {
+                match &expr.kind {
+                    ExprKind::Var(name)
+                        | ExprKind::Abs { param: name, .. }
+                        | ExprKind::Let { name, .. } => {
+                        self.0.insert(name.clone());
+                    },
+                    _ => {},
+                }
+                visit::walk_expr(self, expr);
+            }
+        }
+
+        let mut names = Names(BTreeSet::new());
+        names.visit_expr(self);
+        names.0
+    }
+
+    /// Renames the free occurrences of a variable to a name that doesn't
+    /// appear in the expression, keeping the ids.
+    fn rename_free(&self, from: &Ident, to: &Ident) -> Expr {
+        let kind = match &self.kind {
+            ExprKind::Var(name) if name == from => ExprKind::Var(to.clone()),
+            ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) => return self.clone(),
+            ExprKind::App { callee, arg } => ExprKind::App {
+                callee: Box::new(callee.rename_free(from, to)),
+                arg: Box::new(arg.rename_free(from, to)),
+            },
+            ExprKind::Abs { param, .. } if param == from => return self.clone(),
+            ExprKind::Abs { param, body } => ExprKind::Abs {
+                param: param.clone(),
+                body: Box::new(body.rename_free(from, to)),
+            },
+            ExprKind::Let { name, value, body } => ExprKind::Let {
+                name: name.clone(),
+                value: Box::new(value.rename_free(from, to)),
+                body: Box::new(if name == from { (**body).clone() } else { body.rename_free(from, to) }),
+            },
+        };
+        Expr { kind, id: self.id }
+    }
+
+    /// Decides whether two expressions are equal up to the names of bound
+    /// variables. The ids are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::syntax::parser::parse;
+    /// let parse = |src| parse(src).unwrap().expr;
+    /// assert!(parse("let f = λ x. x in f y").alpha_eq(&parse("let g = λ z. z in g y")));
+    /// assert!(!parse("λ x. λ y. x").alpha_eq(&parse("λ x. λ y. y")));
+    /// ```
+    pub fn alpha_eq(&self, other: &Expr) -> bool {
+        fn go(e1: &Expr, e2: &Expr, bound: &mut Vec<(Ident, Ident)>) -> bool {
+            match (&e1.kind, &e2.kind) {
+                (ExprKind::Var(x1), ExprKind::Var(x2)) => {
+                    // The innermost binder of either variable decides.
+                    let b1 = bound.iter().rposition(|(b, _)| b == x1);
+                    let b2 = bound.iter().rposition(|(_, b)| b == x2);
+                    match (b1, b2) {
+                        (None, None) => x1 == x2,
+                        (b1, b2) => b1 == b2,
+                    }
+                },
+                (ExprKind::Lit(l1), ExprKind::Lit(l2)) => l1 == l2,
+                (ExprKind::Hole(h1), ExprKind::Hole(h2)) => h1 == h2,
+                (ExprKind::App { callee: c1, arg: a1 }, ExprKind::App { callee: c2, arg: a2 }) =>
+                    go(c1, c2, bound) && go(a1, a2, bound),
+                (ExprKind::Abs { param: p1, body: b1 }, ExprKind::Abs { param: p2, body: b2 }) => {
+                    bound.push((p1.clone(), p2.clone()));
+                    let eq = go(b1, b2, bound);
+                    bound.pop();
+                    eq
+                },
+                (
+                    ExprKind::Let { name: n1, value: v1, body: b1 },
+                    ExprKind::Let { name: n2, value: v2, body: b2 },
+                ) => {
+                    if !go(v1, v2, bound) {
+                        return false;
+                    }
+                    bound.push((n1.clone(), n2.clone()));
+                    let eq = go(b1, b2, bound);
+                    bound.pop();
+                    eq
+                },
+                _ => false,
+            }
+        }
+
+        go(self, other, &mut Vec::new())
+    }
+}
+
+/// Finds the variables captured by every `λ`, keyed by the id of the `λ`.
+/// A `λ` captures the variables which are free in it and bound by an
+/// enclosing `λ` or `let`. The variables free in the whole expression are
+/// not captured, they are global.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::Ident, vars::captures}, syntax::parser::parse};
+/// // The inner `λ` captures `x` and `f`, but not the global `g`.
+/// let expr = parse("let f = g in λ x. λ y. f x y").unwrap().expr;
+/// let captures = captures(&expr);
+/// assert_eq!(
+///     captures.values().map(|vars| vars.len()).collect::<Vec<_>>(),
+///     [2, 1],
+/// );
+/// ```
+pub fn captures(expr: &Expr) -> BTreeMap<ExprId, BTreeSet<Ident>> {
+    /// Returns the free variables of the expression, and records the
+    /// captured ones of every `λ` in it.
+    fn go(
+        expr: &Expr,
+        scope: &mut Vec<Ident>,
+        captures: &mut BTreeMap<ExprId, BTreeSet<Ident>>,
+    ) -> BTreeSet<Ident> {
+        match &expr.kind {
+            ExprKind::Var(name) => BTreeSet::from([name.clone()]),
+            ExprKind::Lit(_) | ExprKind::Hole(_) => BTreeSet::new(),
+            ExprKind::App { callee, arg } => {
+                let mut fv = go(callee, scope, captures);
+                fv.extend(go(arg, scope, captures));
+                fv
+            },
+            ExprKind::Abs { param, body } => {
+                scope.push(param.clone());
+                let mut fv = go(body, scope, captures);
+                scope.pop();
+                fv.remove(param);
+                let captured = fv.iter()
+                    .filter(|var| scope.contains(var))
+                    .cloned()
+                    .collect();
+                captures.insert(expr.id, captured);
+                fv
+            },
+            ExprKind::Let { name, value, body } => {
+                let mut fv = go(value, scope, captures);
+                scope.push(name.clone());
+                let mut body_fv = go(body, scope, captures);
+                scope.pop();
+                body_fv.remove(name);
+                fv.extend(body_fv);
+                fv
+            },
+        }
+    }
+
+    let mut captures = BTreeMap::new();
+    go(expr, &mut Vec::new(), &mut captures);
+    captures
+}
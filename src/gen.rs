@@ -0,0 +1,338 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Random generation of well-typed expressions, for property testing.
+
+use crate::{
+    expr::{expr::{Expr, ExprKind, Ident}, lit::Lit},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, subst::Subst, types::Types},
+};
+
+/// A xorshift pseudo-random number generator. It is not good for anything
+/// but testing, but it is small and reproducible from its seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // The state of xorshift must not be zero.
+        Rng { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// The ways to build an expression of a given type, tried in random order.
+#[derive(Debug, Clone, Copy)]
+enum Strategy {
+    Lit,
+    Var,
+    Abs,
+    App,
+    Let,
+}
+
+/// A type-directed generator of random well-typed expressions.
+///
+/// Type variables in the target type are rigid, a generated expression has
+/// exactly the target type, or a more general one.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{gen::Gen, infer::InferCtxt, ty::{Ty, ty_ctxt::TyCtxt, subst::Subst}};
+/// let target = Ty::mk_arrow(Ty::mk_int(), Ty::mk_bool());
+/// let mut gen = Gen::new(42);
+/// let expr = gen.gen_expr(&TyCtxt::new(), &target, 8).unwrap().assign_ids();
+///
+/// let ty = InferCtxt::new().infer(&expr).unwrap();
+/// assert!(Subst::matching(&ty, &target).is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Gen {
+    pub rng: Rng,
+    used_name_id: u32,
+}
+
+impl Gen {
+    pub fn new(seed: u64) -> Gen {
+        Gen { rng: Rng::new(seed), used_name_id: 0 }
+    }
+
+    fn new_name(&mut self) -> Ident {
+        self.used_name_id += 1;
+        Ident::new(format!("x{}", self.used_name_id))
+    }
+
+    fn gen_char(&mut self) -> char {
+        (b'a' + self.rng.below(26) as u8) as char
+    }
+
+    /// A fresh named type variable, which never clashes with the variables
+    /// of inference.
+    fn new_ty_var(&mut self) -> TyVar {
+        self.used_name_id += 1;
+        TyVar::new(format!("p{}", self.used_name_id))
+    }
+
+    /// Generates a random closed type without type variables, of at most the
+    /// given size.
+    pub fn gen_ty(&mut self, size: usize) -> Ty {
+        match self.rng.below(if size == 0 { 6 } else { 12 }) {
+            0 => Ty::mk_int(),
+            1 => Ty::mk_float(),
+            2 => Ty::mk_bool(),
+            3 => Ty::mk_char(),
+            4 => Ty::mk_string(),
+            5 => Ty::mk_unit(),
+            _ => Ty::mk_arrow(self.gen_ty(size / 2), self.gen_ty(size / 2)),
+        }
+    }
+
+    /// Generates a random expression of the given type in the given context,
+    /// whose size is roughly bounded by `size`. Returns `None` if no
+    /// expression is found, when the type is not inhabited in the context.
+    pub fn gen_expr(&mut self, tcx: &TyCtxt, ty: &Ty, size: usize) -> Option<Expr> {
+        let mut strategies = if size == 0 {
+            vec![Strategy::Lit, Strategy::Var, Strategy::Abs]
+        } else {
+            vec![Strategy::Lit, Strategy::Var, Strategy::Abs, Strategy::App, Strategy::Let]
+        };
+        self.rng.shuffle(&mut strategies);
+
+        strategies.into_iter().find_map(|strategy| match strategy {
+            Strategy::Lit => self.gen_lit(ty).map(Expr::mk_lit),
+            Strategy::Var => self.gen_var(tcx, ty, size),
+            Strategy::Abs => match ty {
+                Ty::Arrow(param_ty, ret_ty) => {
+                    let param = self.new_name();
+                    let mut new_tcx = tcx.clone();
+                    new_tcx.insert(
+                        param.clone(),
+                        TyScheme::mk_forall(vec![], (**param_ty).clone()),
+                    );
+                    let body = self.gen_expr(&new_tcx, ret_ty, size.saturating_sub(1))?;
+                    Some(Expr::mk_abs(param, body))
+                },
+                _ => None,
+            },
+            Strategy::App => {
+                let arg_ty = self.gen_ty(2);
+                let size = (size - 1) / 2;
+                let callee = self.gen_expr(
+                    tcx,
+                    &Ty::mk_arrow(arg_ty.clone(), ty.clone()),
+                    size,
+                )?;
+                let arg = self.gen_expr(tcx, &arg_ty, size)?;
+                Some(Expr::mk_app(callee, arg))
+            },
+            Strategy::Let => self.gen_let(tcx, ty, (size - 1) / 2),
+        })
+    }
+
+    /// Generates a random literal of the given type, if it is a base type.
+    pub fn gen_lit(&mut self, ty: &Ty) -> Option<Lit> {
+        match ty {
+            Ty::Int => Some(Lit::Int(self.rng.below(100) as i64)),
+            Ty::Float => Some(Lit::Float(self.rng.below(100) as f64 / 4.0)),
+            Ty::Bool => Some(Lit::Bool(self.rng.below(2) == 0)),
+            Ty::Char => Some(Lit::Char(self.gen_char())),
+            Ty::String => {
+                let len = self.rng.below(4);
+                Some(Lit::String((0..len).map(|_| self.gen_char()).collect()))
+            },
+            Ty::Unit => Some(Lit::Unit),
+            _ => None,
+        }
+    }
+
+    /// Generates a random closed expression of at most the given depth, which
+    /// is not necessarily well-typed, with literals of every base type. There
+    /// are only a few names, so `let`s often shadow and their names are often
+    /// used more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::gen::Gen;
+    /// let mut gen = Gen::new(42);
+    /// assert!((0..100).all(|_| gen.gen_untyped(6).fv().is_empty()));
+    /// ```
+    pub fn gen_untyped(&mut self, depth: usize) -> Expr {
+        self.gen_untyped_in(&mut Vec::new(), depth)
+    }
+
+    fn gen_untyped_in(&mut self, scope: &mut Vec<Ident>, depth: usize) -> Expr {
+        let choice = if depth == 0 { self.rng.below(2) } else { self.rng.below(6) };
+        match choice {
+            0 if !scope.is_empty() => Expr::mk_var(scope[self.rng.below(scope.len())].clone()),
+            0 | 1 => {
+                let ty = self.gen_ty(0);
+                Expr::mk_lit(self.gen_lit(&ty).expect("a type of size 0 is a base type"))
+            },
+            2 | 3 => Expr::mk_app(
+                self.gen_untyped_in(scope, depth - 1),
+                self.gen_untyped_in(scope, depth - 1),
+            ),
+            4 => {
+                let param = Ident::new(format!("x{}", self.rng.below(3)));
+                scope.push(param.clone());
+                let body = self.gen_untyped_in(scope, depth - 1);
+                scope.pop();
+                Expr::mk_abs(param, body)
+            },
+            _ => {
+                let name = Ident::new(format!("x{}", self.rng.below(3)));
+                let value = self.gen_untyped_in(scope, depth - 1);
+                scope.push(name.clone());
+                let body = self.gen_untyped_in(scope, depth - 1);
+                scope.pop();
+                Expr::mk_let(name, value, body)
+            },
+        }
+    }
+
+    /// Generates a variable from the context, applied to as many arguments as
+    /// needed to get the target type.
+    fn gen_var(&mut self, tcx: &TyCtxt, ty: &Ty, size: usize) -> Option<Expr> {
+        let mut candidates = Vec::new();
+        for (name, tys) in tcx.iter() {
+            // The bound variables become flexible variables that can be
+            // matched against the target.
+            let mut subst = Subst::identity();
+            for var in &tys.vars {
+                subst.insert(var.clone(), Ty::mk_var(self.new_ty_var()));
+            }
+            let flexible = tys.vars.iter()
+                .filter_map(|var| match subst.get(var) {
+                    Some(Ty::Var(flexible)) => Some(flexible),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let mut var_ty = tys.ty.apply(&subst);
+            let mut params = Vec::new();
+            loop {
+                if let Some(s) = Subst::matching(&var_ty, ty) {
+                    // Only the instantiated variables may be substituted.
+                    if s.iter().all(|(var, _)| flexible.contains(var)) {
+                        candidates.push((name.clone(), params.clone(), s, flexible.clone()));
+                    }
+                }
+                match var_ty {
+                    Ty::Arrow(param_ty, ret_ty) if params.len() < size => {
+                        params.push(*param_ty);
+                        var_ty = *ret_ty;
+                    },
+                    _ => break,
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let (name, params, mut subst, flexible) =
+            candidates.swap_remove(self.rng.below(candidates.len()));
+        // Variables that only occur in the parameters are picked at random.
+        for var in flexible {
+            if subst.get(&var).is_none() {
+                let ground = self.gen_ty(1);
+                subst.insert(var, ground);
+            }
+        }
+        let size = size.saturating_sub(1) / params.len().max(1);
+        params.iter().try_fold(Expr::mk_var(name), |callee, param_ty| {
+            let arg = self.gen_expr(tcx, &param_ty.apply(&subst), size)?;
+            Some(Expr::mk_app(callee, arg))
+        })
+    }
+
+    /// Generates a `let`, sometimes with a polymorphic bound value.
+    fn gen_let(&mut self, tcx: &TyCtxt, ty: &Ty, size: usize) -> Option<Expr> {
+        let name = self.new_name();
+        let poly = self.rng.below(2) == 0;
+        let (value, tys) = if poly {
+            // A value of type `p -> p` for a fresh rigid `p` is generalized.
+            let var = self.new_ty_var();
+            let value_ty = Ty::mk_arrow(Ty::mk_var(var.clone()), Ty::mk_var(var.clone()));
+            let value = self.gen_expr(tcx, &value_ty, size)?;
+            (value, TyScheme::mk_forall(vec![var], value_ty))
+        } else {
+            let value_ty = self.gen_ty(2);
+            let value = self.gen_expr(tcx, &value_ty, size)?;
+            (value, TyScheme::mk_forall(vec![], value_ty))
+        };
+        let mut new_tcx = tcx.clone();
+        new_tcx.insert(name.clone(), tys);
+        let body = self.gen_expr(&new_tcx, ty, size)?;
+        Some(Expr::mk_let(name, value, body))
+    }
+}
+
+/// Proposes smaller versions of an expression for shrinking a counterexample:
+/// its sub-expressions, and the expression with one of its sub-expressions
+/// shrunk. The candidates need not be well-typed, or even closed.
+pub fn shrink(expr: &Expr) -> Vec<Expr> {
+    let mut candidates = Vec::new();
+    match &expr.kind {
+        ExprKind::Var(_) | ExprKind::Hole(_) => {},
+        ExprKind::Lit(Lit::Int(int)) if *int != 0 =>
+            candidates.push(Expr::mk_lit(Lit::Int(int / 2))),
+        ExprKind::Lit(_) => {},
+        ExprKind::App { callee, arg } => {
+            candidates.push((**callee).clone());
+            candidates.push((**arg).clone());
+            for c in shrink(callee) {
+                candidates.push(Expr::mk_app(c, (**arg).clone()));
+            }
+            for a in shrink(arg) {
+                candidates.push(Expr::mk_app((**callee).clone(), a));
+            }
+        },
+        ExprKind::Abs { param, body } => {
+            candidates.push((**body).clone());
+            for b in shrink(body) {
+                candidates.push(Expr::mk_abs(param.clone(), b));
+            }
+        },
+        ExprKind::Let { name, value, body } => {
+            candidates.push((**body).clone());
+            candidates.push((**value).clone());
+            for v in shrink(value) {
+                candidates.push(Expr::mk_let(name.clone(), v, (**body).clone()));
+            }
+            for b in shrink(body) {
+                candidates.push(Expr::mk_let(name.clone(), (**value).clone(), b));
+            }
+        },
+    }
+    candidates
+}
+
+/// Shrinks a counterexample as long as it keeps failing the property, and
+/// returns the smallest one found.
+pub fn minimize(mut expr: Expr, fails: impl Fn(&Expr) -> bool) -> Expr {
+    while let Some(smaller) = shrink(&expr).into_iter().find(|c| fails(c)) {
+        expr = smaller;
+    }
+    expr
+}
@@ -0,0 +1,35 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::fmt;
+
+use crate::{
+    expr::{expr::Ident, expr_id::ExprId},
+    ty::{Ty, ty_scheme::TyScheme},
+};
+
+/// A typed hole found during inference, with the type its context expects
+/// after the final substitution and the bindings in scope that fit it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoleReport {
+    pub id: ExprId,
+    pub name: Option<Ident>,
+    pub ty: Ty,
+
+    /// The bindings in the context of the hole whose instantiated type
+    /// unifies with the type of the hole, in the order of their names.
+    pub fits: Vec<(Ident, TyScheme)>,
+}
+
+impl fmt::Display for HoleReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "hole _{} : {}", name, self.ty)?,
+            None => write!(f, "hole _ : {}", self.ty)?,
+        }
+        for (name, tys) in &self.fits {
+            write!(f, "\n  {} : {}", name, tys)?;
+        }
+        Ok(())
+    }
+}
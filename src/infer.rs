@@ -1,18 +1,106 @@
 // Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
 // root for license information.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{ty::{TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, Ty, types::Types, subst::Subst}, expr::{expr::{Expr, ExprKind}, lit::Lit}, error::{TyResult, TyError}};
+use crate::{derivation::Derivation, hole::HoleReport, ty::{TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, Ty, types::Types, subst::Subst}, expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit}, error::{TyResult, TyError, Note, Warning}, visit::visit_mut::{self, VisitMut}};
+
+/// The result of [`InferCtxt::infer_recovering`], the types of all nodes that
+/// could be inferred together with every type error found on the way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovered {
+    /// The type of the whole expression, [`Ty::Error`] if the root node itself
+    /// failed to type check.
+    pub ty: Ty,
+
+    /// The type of every node, keyed by its id. Nodes that failed to type
+    /// check and nodes whose type depends on them have [`Ty::Error`] in them.
+    pub types: BTreeMap<ExprId, Ty>,
+
+    /// The generalized type scheme of the name bound by every `let` node,
+    /// keyed by the id of the node.
+    pub schemes: BTreeMap<ExprId, TyScheme>,
+
+    /// The type errors, in the order they were found, together with the id
+    /// of the node which caused them.
+    pub errors: Vec<(ExprId, TyError)>,
+
+    /// The warnings, together with the id of the node they are about.
+    pub warnings: Vec<(ExprId, Warning)>,
+}
+
+impl Recovered {
+    /// Replaces every literal of the expression with the literal at its
+    /// inferred type, see [`Lit::at_ty`]. The evaluator has no types, so an
+    /// integer literal inferred as `float` must become a float literal before
+    /// evaluation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{eval::{eval, Value}, infer::InferCtxt, syntax::parser::parse};
+    /// let expr = parse("(λ i. (λ u. i 1) (i 2.5)) (λ x. x)").unwrap().expr;
+    /// let recovered = InferCtxt::new().infer_recovering(&expr);
+    /// assert_eq!(recovered.ty.to_string(), "float");
+    /// assert_eq!(eval(&recovered.resolve_lits(&expr)), Ok(Value::Float(1.0)));
+    /// ```
+    pub fn resolve_lits(&self, expr: &Expr) -> Expr {
+        struct Resolver<'a>(&'a BTreeMap<ExprId, Ty>);
+
+        impl VisitMut for Resolver<'_> {
+            fn visit_expr_mut(&mut self, expr: &mut Expr) {
+                match &mut expr.kind {
+                    ExprKind::Lit(lit) => if let Some(ty) = self.0.get(&expr.id) {
+                        *lit = lit.at_ty(ty);
+                    },
+                    _ => visit_mut::walk_expr(self, expr),
+                }
+            }
+        }
+
+        let mut expr = expr.clone();
+        Resolver(&self.types).visit_expr_mut(&mut expr);
+        expr
+    }
+}
 
 pub struct InferCtxt {
     used_type_var_id: u32,
+
+    /// Finished derivations of the nodes visited so far, only recorded while
+    /// building a derivation tree with [`InferCtxt::derive`].
+    trace: Option<Vec<Derivation>>,
+
+    /// The errors collected and the types of the nodes visited so far, only
+    /// recorded while inferring with [`InferCtxt::infer_recovering`].
+    recovery: Option<Recovered>,
+
+    /// Whether to attach hints to type errors, disabled in the scratch
+    /// contexts used to compute the hints themselves.
+    hints: bool,
+
+    /// The typed holes visited so far with their contexts, only recorded
+    /// while [`InferCtxt::infer_holes`] runs. The types are only resolved
+    /// when inference finishes.
+    holes: Option<Vec<(HoleReport, TyCtxt)>>,
+
+    /// The numeric type variables of the integer literals visited so far,
+    /// to default the ones left unknown when inference finishes.
+    nums: Vec<(ExprId, TyVar)>,
+
+    warnings: Vec<(ExprId, Warning)>,
 }
 
 impl InferCtxt {
     pub fn new() -> InferCtxt {
         InferCtxt {
             used_type_var_id: 0,
+            trace: None,
+            recovery: None,
+            hints: true,
+            holes: None,
+            nums: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -22,16 +110,49 @@ impl InferCtxt {
         TyVar::Unknown(id)
     }
 
+    fn new_num_var(&mut self) -> TyVar {
+        let id = self.used_type_var_id;
+        self.used_type_var_id += 1;
+        TyVar::Num(id)
+    }
+
+    /// The warnings about everything inferred with this context so far, with
+    /// the id of the node each one is about.
+    pub fn warnings(&self) -> &[(ExprId, Warning)] {
+        &self.warnings
+    }
+
+    /// Reports a type error caused by the given node. The error is collected
+    /// and the node gets the error type when we are recovering from errors,
+    /// otherwise it is returned as is.
+    fn recover(&mut self, id: ExprId, err: TyError, subst: Subst) -> TyResult<(Subst, Ty)> {
+        match &mut self.recovery {
+            Some(recovered) => {
+                recovered.errors.push((id, err));
+                Ok((subst, Ty::mk_error()))
+            },
+            None => Err(err),
+        }
+    }
+
     /// Abstracts a type over all type variables which are free in the type but
     /// not free in the given type environment.
     ///
+    /// Numeric type variables are never generalized, they stay monomorphic
+    /// so that the uses of a binding decide them, or else they are defaulted
+    /// when inference finishes.
+    ///
     /// ```text
     /// Γ ⊢ e : σ     σ′ ∉ ftv(Γ)
     /// ------------------------- (Gen)
     ///      Γ ⊢ e : ∀ α . σ
     /// ```
     pub fn generalize(&self, tcx: TyCtxt, ty: Ty) -> TyScheme {
-        let vars = ty.ftv().difference(&tcx.ftv()).cloned().collect::<Vec<_>>();
+        let vars = ty.ftv()
+            .difference(&tcx.ftv())
+            .filter(|var| !var.is_num())
+            .cloned()
+            .collect::<Vec<_>>();
         TyScheme::mk_forall(vars, ty)
     }
 
@@ -44,15 +165,66 @@ impl InferCtxt {
     ///       Γ ⊢ e : σ
     /// ```
     pub fn instantiate(&mut self, tys: TyScheme) -> Ty {
-        let mut subst = Subst::identity();
+        // The fresh variables may have the names of other bound variables,
+        // so they are renamed at once rather than substituted.
+        let mut names = BTreeMap::new();
         for var in tys.vars {
-            subst.insert(var, Ty::mk_var(self.new_type_var()));
+            names.insert(var, self.new_type_var());
+        }
+        tys.ty.rename(&names)
+    }
+
+    /// Defaults the numeric type variables of the type of a top-level binding
+    /// which are not free in the context to `int`, and returns the
+    /// substitution with the defaults added. No later use can decide them.
+    fn default_nums(&mut self, tcx: &TyCtxt, ty: &Ty, subst: Subst) -> Subst {
+        let vars = ty.ftv()
+            .difference(&tcx.ftv())
+            .filter(|var| var.is_num())
+            .cloned()
+            .collect();
+        self.default_to_int(vars, subst)
+    }
+
+    /// Defaults the numeric type variables that are still unknown when
+    /// inference finishes.
+    fn default_rest(&mut self, subst: Subst) -> Subst {
+        let vars = self.nums.iter()
+            .filter_map(|(_, var)| match Ty::mk_var(var.clone()).apply(&subst) {
+                Ty::Var(var @ TyVar::Num(_)) => Some(var),
+                _ => None,
+            })
+            .collect();
+        let subst = self.default_to_int(vars, subst);
+        self.nums.clear();
+        subst
+    }
+
+    /// Binds numeric type variables to `int`, and warns about each one at
+    /// the first literal which has it as its type.
+    fn default_to_int(&mut self, vars: BTreeSet<TyVar>, subst: Subst) -> Subst {
+        if vars.is_empty() {
+            return subst;
+        }
+        let mut defaults = Subst::identity();
+        for (id, var) in &self.nums {
+            if let Ty::Var(var) = Ty::mk_var(var.clone()).apply(&subst) {
+                if vars.contains(&var) && defaults.get(&var).is_none() {
+                    self.warnings.push((*id, Warning::Defaulted(var.clone())));
+                    defaults.insert(var, Ty::mk_int());
+                }
+            }
         }
-        tys.ty.apply(&subst)
+        defaults.compose(&subst)
     }
 
     fn infer_impl(&mut self, tcx: TyCtxt, expr: &Expr) -> TyResult<(Subst, Ty)> {
-        match expr.kind {
+        // Derivations of sub-expressions are pushed onto the trace as they
+        // finish, everything above this mark are the premises of this node.
+        let premises_start = self.trace.as_ref().map(Vec::len);
+        let traced_tcx = premises_start.map(|_| tcx.clone());
+
+        let (subst, ty) = match expr.kind {
             // x : σ ∈ Γ
             // --------- (Var)
             // Γ ⊢ x : σ
@@ -63,16 +235,32 @@ impl InferCtxt {
                 if let Some(tys) = tcx.get(name) {
                     Ok((Subst::identity(), self.instantiate(tys.clone())))
                 } else {
-                    Err(TyError::Unknown(format!("unbound variable {name}")))
+                    let mut err = TyError::mk_unbound_var(name.clone());
+                    if let Some(similar) = similar_name(&tcx, name) {
+                        err = err.with_note(Note::DidYouMean(similar));
+                    }
+                    self.recover(expr.id, err, Subst::identity())
                 }
             },
 
             ExprKind::Lit(ref lit) => {
                 match lit {
+                    // An integer literal is `int` or `float`, whichever
+                    // the rest of the program needs, so its type is a new
+                    // numeric type variable.
+                    //
+                    // --−−−−−−−--------------- (Int)
+                    // Γ ⊢ [0-9]+ : α   Num α
+                    Lit::Int(_) => {
+                        let var = self.new_num_var();
+                        self.nums.push((expr.id, var.clone()));
+                        Ok((Subst::identity(), Ty::mk_var(var)))
+                    },
+
                     //
-                    // --−−−−−−−------- (Int)
-                    // Γ ⊢ [0-9]+ : int
-                    Lit::Int(_) => Ok((Subst::identity(), Ty::mk_int())),
+                    // --−−−−−−−------------------ (Float)
+                    // Γ ⊢ [0-9]+ "." [0-9]+ : float
+                    Lit::Float(_) => Ok((Subst::identity(), Ty::mk_float())),
 
                     //
                     // --−−−−−−−------ (Bool)
@@ -81,9 +269,41 @@ impl InferCtxt {
                     // --−−−−−−−------- (Bool)
                     // Γ ⊢ false : bool
                     Lit::Bool(_) => Ok((Subst::identity(), Ty::mk_bool())),
+
+                    //
+                    // --−−−−−−−------ (Char)
+                    // Γ ⊢ 'c' : char
+                    Lit::Char(_) => Ok((Subst::identity(), Ty::mk_char())),
+
+                    //
+                    // --−−−−−−−------------ (String)
+                    // Γ ⊢ "..." : string
+                    Lit::String(_) => Ok((Subst::identity(), Ty::mk_string())),
+
+                    //
+                    // --−−−−−−−----- (Unit)
+                    // Γ ⊢ () : unit
+                    Lit::Unit => Ok((Subst::identity(), Ty::mk_unit())),
                 }
             },
 
+            //
+            // --------- (Hole)
+            // Γ ⊢ _ : τ
+            ExprKind::Hole(ref name) => {
+                let new_ty = Ty::mk_var(self.new_type_var());
+                let hole = HoleReport {
+                    id: expr.id,
+                    name: name.clone(),
+                    ty: new_ty.clone(),
+                    fits: Vec::new(),
+                };
+                if let Some(holes) = &mut self.holes {
+                    holes.push((hole, tcx));
+                }
+                Ok((Subst::identity(), new_ty))
+            },
+
             //   Γ, x : τ ⊢ e : τ′
             // −−−−−−−−−−−−−−------- (Abs)
             // Γ ⊢ λ x . e : τ → τ′
@@ -96,7 +316,7 @@ impl InferCtxt {
                     param.clone(),
                     TyScheme::mk_forall(vec![], new_ty.clone()),
                 );
-                let (subst, ty) = self.infer_impl(new_tcx, &body)?;
+                let (subst, ty) = self.infer_impl(new_tcx, body)?;
 
                 // FIXME: Remove this `clone` in `subst.clone()`.
                 Ok((subst.clone(), Ty::mk_arrow(new_ty.apply(&subst), ty)))
@@ -107,31 +327,359 @@ impl InferCtxt {
             //          Γ ⊢ e0(e1) : τ′
             ExprKind::App { ref callee, ref arg } => {
                 let new_ty = Ty::mk_var(self.new_type_var());
-                let (s1, ty1) = self.infer_impl(tcx.clone(), &callee)?;
-                let (s2, ty2) = self.infer_impl(tcx.apply(&s1), &arg)?;
-                let s3 = Subst::mgu(
-                    ty1.apply(&s2),
-                    Ty::mk_arrow(ty2, new_ty.clone()),
-                )?;
-                Ok((s3.compose(&s2).compose(&s1), new_ty.apply(&s3)))
+                let (s1, ty1) = self.infer_impl(tcx.clone(), callee)?;
+                let (s2, ty2) = self.infer_impl(tcx.apply(&s1), arg)?;
+                let callee_ty = ty1.apply(&s2);
+                match Subst::mgu(callee_ty.clone(), Ty::mk_arrow(ty2.clone(), new_ty.clone())) {
+                    Ok(s3) =>
+                        Ok((s3.compose(&s2).compose(&s1), new_ty.apply(&s3))),
+                    Err(err) if self.hints => {
+                        let err = self.explain_app(&tcx, callee, arg, &callee_ty, &ty2, err);
+                        self.recover(expr.id, err, s2.compose(&s1))
+                    },
+                    Err(err) =>
+                        self.recover(expr.id, err, s2.compose(&s1)),
+                }
             },
 
             // Γ ⊢ e0 : σ     Γ, x : σ ⊢ e1 : τ
             // −------------−−−−−−−−−−−−−−−−−−−− (Let)
             //     Γ ⊢ let x = e0 in e1 : τ
             ExprKind::Let { ref name, ref value, ref body } => {
-                let (s1, t1) = self.infer_impl(tcx.clone(), &value)?;
+                let (s1, t1) = self.infer_impl(tcx.clone(), value)?;
                 let s = self.generalize(tcx.apply(&s1), t1);
+                if let Some(recovered) = &mut self.recovery {
+                    recovered.schemes.insert(expr.id, s.clone());
+                }
                 let mut new_tcx = tcx;
                 new_tcx.insert(name.clone(), s.clone());
-                let (s2, t2) = self.infer_impl(new_tcx.apply(&s1), &body)?;
+                let (s2, t2) = self.infer_impl(new_tcx.apply(&s1), body)?;
                 Ok((s2.compose(&s1), t2))
             },
+        }?;
+
+        if let (Some(trace), Some(start), Some(tcx)) =
+            (&mut self.trace, premises_start, traced_tcx)
+        {
+            let premises = trace.split_off(start);
+            trace.push(Derivation::new(tcx, expr.clone(), ty.clone(), premises));
+        }
+        if let Some(recovered) = &mut self.recovery {
+            recovered.types.insert(expr.id, ty.clone());
         }
+        Ok((subst, ty))
     }
 
+    /// Attaches hints for common mistakes to the error of an application
+    /// `callee arg` that failed to type check.
+    fn explain_app(
+        &self,
+        tcx: &TyCtxt,
+        callee: &Expr,
+        arg: &Expr,
+        callee_ty: &Ty,
+        arg_ty: &Ty,
+        mut err: TyError,
+    ) -> TyError {
+        match (callee_ty, arg_ty) {
+            (ty, _) if ty.is_base() || matches!(ty, Ty::Var(TyVar::Num(_))) => {
+                let ty = ty.default_nums();
+                err = err.with_note(match callee.kind {
+                    ExprKind::App { .. } => Note::ExtraArgument(ty),
+                    _ => Note::NotAFunction(ty),
+                });
+            },
+            // The argument would fit if it was applied to one more argument.
+            (Ty::Arrow(param_ty, _), Ty::Arrow(_, ret_ty))
+                if Subst::mgu(*param_ty.clone(), *ret_ty.clone()).is_ok() =>
+            {
+                err = err.with_note(Note::MissingArgument {
+                    found: arg_ty.clone(),
+                    expected: *param_ty.clone(),
+                });
+            },
+            _ => {},
+        }
+
+        // Try the application with the two arguments swapped, if there are two.
+        if let ExprKind::App { callee: ref f, arg: ref first } = callee.kind {
+            let swapped = Expr::mk_app(Expr::mk_app(*f.clone(), arg.clone()), *first.clone());
+            let mut scratch = InferCtxt {
+                used_type_var_id: self.used_type_var_id,
+                trace: None,
+                recovery: None,
+                hints: false,
+                holes: None,
+                nums: Vec::new(),
+                warnings: Vec::new(),
+            };
+            if scratch.infer_impl(tcx.clone(), &swapped).is_ok() {
+                err = err.with_note(Note::SwappedArguments);
+            }
+        }
+        err
+    }
+
+    /// Infers the type of an expression, stops at the first type error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, infer::InferCtxt};
+    /// # use type_infer_rs::error::{TyErrorKind, Note};
+    /// // let id = λ x. x in idd 1
+    /// let expr = Expr::mk_let(
+    ///     Ident::new("id".to_string()),
+    ///     Expr::mk_abs(
+    ///         Ident::new("x".to_string()),
+    ///         Expr::mk_var(Ident::new("x".to_string())),
+    ///     ),
+    ///     Expr::mk_app(
+    ///         Expr::mk_var(Ident::new("idd".to_string())),
+    ///         Expr::mk_lit(Lit::Int(1)),
+    ///     ),
+    /// ).assign_ids();
+    ///
+    /// let err = InferCtxt::new().infer(&expr).unwrap_err();
+    /// assert_eq!(err.kind, TyErrorKind::UnboundVar(Ident::new("idd".to_string())));
+    /// assert_eq!(err.notes, vec![Note::DidYouMean(Ident::new("id".to_string()))]);
+    /// ```
     pub fn infer(&mut self, expr: &Expr) -> TyResult<Ty> {
-        let (s, t) = self.infer_impl(TyCtxt::new(), expr)?;
+        self.infer_in(&TyCtxt::new(), expr)
+    }
+
+    /// Infers the type of an expression in a type context, like the one of
+    /// the [`crate::prelude`].
+    pub fn infer_in(&mut self, tcx: &TyCtxt, expr: &Expr) -> TyResult<Ty> {
+        let (s, t) = self.infer_root(tcx.clone(), expr)?;
         Ok(t.apply(&s))
     }
+
+    /// Infers the type of a whole expression, and defaults the numeric type
+    /// variables left unknown in it.
+    fn infer_root(&mut self, tcx: TyCtxt, expr: &Expr) -> TyResult<(Subst, Ty)> {
+        self.nums.clear();
+        let (s, t) = self.infer_impl(tcx, expr)?;
+        Ok((self.default_rest(s), t))
+    }
+
+    /// Infers the type schemes of a group of mutually recursive bindings in
+    /// the given context, in the order of the bindings.
+    ///
+    /// Every binding is monomorphic in the group itself and generalized only
+    /// after the whole group is inferred.
+    ///
+    /// ```text
+    /// Γ, x1 : τ1, ..., xN : τN ⊢ eI : τI     σI = gen(Γ, τI)
+    /// ------------------------------------------------------ (Rec)
+    ///             Γ ⊢ x1 = e1, ..., xN = eN : σ1, ..., σN
+    /// ```
+    pub fn infer_bindings(
+        &mut self,
+        tcx: &TyCtxt,
+        bindings: &[(Ident, &Expr)],
+    ) -> TyResult<Vec<TyScheme>> {
+        let vars = bindings.iter()
+            .map(|_| Ty::mk_var(self.new_type_var()))
+            .collect::<Vec<_>>();
+        let mut rec_tcx = tcx.clone();
+        for ((name, _), var) in bindings.iter().zip(&vars) {
+            rec_tcx.insert(name.clone(), TyScheme::mk_forall(vec![], var.clone()));
+        }
+
+        self.nums.clear();
+        let mut subst = Subst::identity();
+        for ((_, expr), var) in bindings.iter().zip(&vars) {
+            let (s1, ty) = self.infer_impl(rec_tcx.apply(&subst), expr)?;
+            subst = s1.compose(&subst);
+            let s2 = Subst::mgu(var.apply(&subst), ty.apply(&subst))?;
+            subst = s2.compose(&subst);
+        }
+
+        let tcx = tcx.apply(&subst);
+        for var in &vars {
+            subst = self.default_nums(&tcx, &var.apply(&subst), subst);
+        }
+        let subst = self.default_rest(subst);
+        Ok(vars.iter()
+            .map(|var| self.generalize(tcx.clone(), var.apply(&subst)))
+            .collect())
+    }
+
+    /// Infers the type of an expression with typed holes in it, and reports
+    /// the expected type of each hole together with the bindings in scope
+    /// that fit it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, infer::InferCtxt};
+    /// // let id = λ x. x in _ 1
+    /// let expr = Expr::mk_let(
+    ///     Ident::new("id".to_string()),
+    ///     Expr::mk_abs(
+    ///         Ident::new("x".to_string()),
+    ///         Expr::mk_var(Ident::new("x".to_string())),
+    ///     ),
+    ///     Expr::mk_app(
+    ///         Expr::mk_hole(None),
+    ///         Expr::mk_lit(Lit::Int(1)),
+    ///     ),
+    /// ).assign_ids();
+    ///
+    /// let (_, holes) = InferCtxt::new().infer_holes(&expr).unwrap();
+    /// assert_eq!(holes[0].ty.to_string(), "int -> ?1");
+    /// assert_eq!(holes[0].fits[0].0, Ident::new("id".to_string()));
+    /// ```
+    pub fn infer_holes(&mut self, expr: &Expr) -> TyResult<(Ty, Vec<HoleReport>)> {
+        self.infer_holes_in(&TyCtxt::new(), expr)
+    }
+
+    /// Infers the type of an expression with typed holes in it in a type
+    /// context, see [`InferCtxt::infer_holes`]. The bindings of the context
+    /// are candidates to fit the holes too.
+    pub fn infer_holes_in(&mut self, tcx: &TyCtxt, expr: &Expr) -> TyResult<(Ty, Vec<HoleReport>)> {
+        self.holes = Some(Vec::new());
+        let result = self.infer_root(tcx.clone(), expr);
+        let holes = self.holes.take().unwrap_or_default();
+        let (s, t) = result?;
+        let holes = holes.into_iter()
+            .map(|(hole, tcx)| self.fit_hole(hole, tcx.apply(&s), &s))
+            .collect();
+        Ok((t.apply(&s), holes))
+    }
+
+    /// Resolves the type of a hole and finds the bindings in its context that
+    /// fit the type.
+    ///
+    /// The unknown types in the type of the hole may become anything, but the
+    /// ones free in a binding are not for the hole to decide. A binding only
+    /// fits if unifying leaves them distinct unknown types, so that a binding
+    /// whose type is unknown doesn't fit every hole.
+    fn fit_hole(&mut self, hole: HoleReport, tcx: TyCtxt, subst: &Subst) -> HoleReport {
+        let ty = hole.ty.apply(subst);
+        let fits = tcx.iter()
+            .filter(|(_, tys)| {
+                let fixed = tys.ftv();
+                let candidate = self.instantiate((*tys).clone());
+                let Ok(s) = Subst::mgu(candidate, ty.clone()) else {
+                    return false;
+                };
+                let images = fixed.into_iter()
+                    .map(|var| Ty::mk_var(var).apply(&s))
+                    .collect::<Vec<_>>();
+                images.iter().enumerate().all(|(i, image)| {
+                    matches!(image, Ty::Var(_)) && !images[..i].contains(image)
+                })
+            })
+            .map(|(name, tys)| (name.clone(), tys.clone()))
+            .collect();
+        HoleReport { ty, fits, ..hole }
+    }
+
+    /// Infers the type of an expression without stopping at the first type
+    /// error.
+    ///
+    /// A node that fails to type check is given the error type, which unifies
+    /// with any other type, and inference continues with the rest of the
+    /// program. Errors that would only be caused by the error type are not
+    /// reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, infer::InferCtxt};
+    /// // (1 true) (f 2)
+    /// let expr = Expr::mk_app(
+    ///     Expr::mk_app(Expr::mk_lit(Lit::Int(1)), Expr::mk_lit(Lit::Bool(true))),
+    ///     Expr::mk_app(
+    ///         Expr::mk_var(Ident::new("f".to_string())),
+    ///         Expr::mk_lit(Lit::Int(2)),
+    ///     ),
+    /// ).assign_ids();
+    ///
+    /// // Both `1 true` and the unbound variable `f` are reported, but the
+    /// // outer application is not, because its callee has the error type.
+    /// let recovered = InferCtxt::new().infer_recovering(&expr);
+    /// assert_eq!(recovered.errors.len(), 2);
+    /// assert_eq!(recovered.types[&expr.id], recovered.ty);
+    /// ```
+    pub fn infer_recovering(&mut self, expr: &Expr) -> Recovered {
+        self.infer_recovering_in(&TyCtxt::new(), expr)
+    }
+
+    /// Infers the type of an expression in a type context without stopping
+    /// at the first type error, see [`InferCtxt::infer_recovering`].
+    pub fn infer_recovering_in(&mut self, tcx: &TyCtxt, expr: &Expr) -> Recovered {
+        self.recovery = Some(Recovered {
+            ty: Ty::mk_error(),
+            types: BTreeMap::new(),
+            schemes: BTreeMap::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        });
+        let warnings_start = self.warnings.len();
+        let result = self.infer_root(tcx.clone(), expr);
+        let recovered = self.recovery.take().expect("recovery is enabled");
+        let (s, t) = result.expect("errors are recovered");
+        Recovered {
+            ty: t.apply(&s),
+            types: recovered.types.iter()
+                .map(|(id, ty)| (*id, ty.apply(&s)))
+                .collect(),
+            schemes: recovered.schemes.iter()
+                .map(|(id, tys)| (*id, tys.apply(&s)))
+                .collect(),
+            errors: recovered.errors,
+            warnings: self.warnings[warnings_start..].to_vec(),
+        }
+    }
+
+    /// Infers the type of an expression like [`InferCtxt::infer`], and returns
+    /// the complete typing derivation of it.
+    pub fn derive(&mut self, expr: &Expr) -> TyResult<Derivation> {
+        self.trace = Some(Vec::new());
+        let result = self.infer_root(TyCtxt::new(), expr);
+        let trace = self.trace.take().unwrap_or_default();
+        let (s, _) = result?;
+        let derivation = trace.last().expect("the root is always traced last");
+        Ok(derivation.apply(&s))
+    }
+}
+
+impl Default for InferCtxt {
+    fn default() -> InferCtxt {
+        InferCtxt::new()
+    }
+}
+
+/// Finds the name in the context closest to an unbound name, if any is close
+/// enough to be a plausible typo.
+fn similar_name(tcx: &TyCtxt, name: &Ident) -> Option<Ident> {
+    let max_distance = usize::max(1, name.name.chars().count() / 3);
+    tcx.iter()
+        .map(|(candidate, _)| (edit_distance(&name.name, &candidate.name), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
 }
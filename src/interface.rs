@@ -0,0 +1,194 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Interface files, the exports of checked modules written to disk, so that
+//! the modules importing them can be checked without checking them again.
+//!
+//! An interface file is a single S-expression in the format of
+//! [`crate::syntax::sexp`]:
+//!
+//! ```text
+//! interface ::= (interface version module hash (imports (module hash)*) ctxt)
+//! ```
+//!
+//! The hash is the hash of the source text of the module, an interface is
+//! only loaded with the source it was written from. The exports also depend
+//! on the interfaces of the imported modules, so the hash of each of them is
+//! recorded too, and an interface is only loaded with the same imported
+//! interfaces. The language has no data type declarations, so the type
+//! context is all there is to export.
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use crate::{
+    error::{InterfaceError, InterfaceResult, ParseResult},
+    expr::expr::Ident,
+    syntax::sexp::{FromSexp, Sexp, ToSexp},
+    ty::ty_ctxt::TyCtxt,
+};
+
+/// The version of the format, increased when the format changes.
+pub const VERSION: u32 = 2;
+
+/// The exports of a module, with the hash of the source they were inferred
+/// from and the hashes of the interfaces of the modules it imports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub module: Ident,
+    pub hash: u64,
+    pub imports: BTreeMap<Ident, u64>,
+    pub exports: TyCtxt,
+}
+
+impl Interface {
+    pub fn new(module: Ident, src: &str, exports: TyCtxt) -> Interface {
+        Interface { module, hash: hash(src), imports: BTreeMap::new(), exports }
+    }
+
+    /// Records the interfaces of the imported modules the exports were
+    /// inferred with.
+    pub fn with_imports(mut self, imports: &BTreeMap<Ident, Interface>) -> Interface {
+        self.imports = imports.iter()
+            .map(|(module, interface)| (module.clone(), interface.fingerprint()))
+            .collect();
+        self
+    }
+
+    /// The hash of the whole interface, which the interfaces of the importing
+    /// modules record.
+    pub fn fingerprint(&self) -> u64 {
+        hash(&self.to_string())
+    }
+
+    /// Reads an interface from the text of an interface file, and checks that
+    /// it was written from the given source text and with the given interfaces
+    /// of the imported modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use type_infer_rs::{expr::expr::Ident, interface::Interface};
+    /// # use type_infer_rs::syntax::parser::parse_signature;
+    /// let name = |name: &str| Ident::new(name.to_string());
+    /// let src = "let id = λ x. x;";
+    /// let exports = parse_signature("val id : forall a . a -> a;").unwrap();
+    /// let id = Interface::new(name("id"), src, exports);
+    /// let text = id.to_string();
+    ///
+    /// assert!(Interface::read(&text, src, &BTreeMap::new()).is_ok());
+    /// assert!(Interface::read(&text, "let id = λ y. y;", &BTreeMap::new()).is_err());
+    ///
+    /// // An importer is stale once the interface of `id` changes.
+    /// let main_src = "import id; let main = id 1;";
+    /// let exports = parse_signature("val main : int;").unwrap();
+    /// let imports = BTreeMap::from([(name("id"), id)]);
+    /// let text = Interface::new(name("main"), main_src, exports).with_imports(&imports).to_string();
+    /// assert!(Interface::read(&text, main_src, &imports).is_ok());
+    ///
+    /// let exports = parse_signature("val id : int -> int;").unwrap();
+    /// let imports = BTreeMap::from([(name("id"), Interface::new(name("id"), src, exports))]);
+    /// assert!(Interface::read(&text, main_src, &imports).is_err());
+    /// ```
+    pub fn read(
+        text: &str,
+        src: &str,
+        imports: &BTreeMap<Ident, Interface>,
+    ) -> InterfaceResult<Interface> {
+        let sexp = Sexp::parse(text)?;
+        if let ("interface", [version, ..]) = sexp.as_form()? {
+            let version = version.as_atom()?
+                .parse()
+                .map_err(|_| version.error("expected a number"))?;
+            if version != VERSION {
+                return Err(InterfaceError::Version(version));
+            }
+        }
+        let interface = Interface::from_sexp(&sexp)?;
+        let imports_match = interface.imports.len() == imports.len()
+            && interface.imports.iter().all(|(module, fingerprint)| {
+                imports.get(module).is_some_and(|import| import.fingerprint() == *fingerprint)
+            });
+        if interface.hash != hash(src) || !imports_match {
+            return Err(InterfaceError::Stale(interface.module));
+        }
+        Ok(interface)
+    }
+
+    /// Loads the interface file at the given path, see [`Interface::read`].
+    pub fn load(
+        path: impl AsRef<Path>,
+        src: &str,
+        imports: &BTreeMap<Ident, Interface>,
+    ) -> InterfaceResult<Interface> {
+        Interface::read(&fs::read_to_string(path)?, src, imports)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> InterfaceResult<()> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+}
+
+impl fmt::Display for Interface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_sexp())
+    }
+}
+
+impl ToSexp for Interface {
+    fn to_sexp(&self) -> Sexp {
+        Sexp::mk_list(vec![
+            Sexp::mk_atom("interface"),
+            Sexp::mk_atom(VERSION.to_string()),
+            self.module.to_sexp(),
+            hash_to_sexp(self.hash),
+            Sexp::mk_list(
+                std::iter::once(Sexp::mk_atom("imports"))
+                    .chain(self.imports.iter().map(|(module, fingerprint)| {
+                        Sexp::mk_list(vec![module.to_sexp(), hash_to_sexp(*fingerprint)])
+                    }))
+                    .collect(),
+            ),
+            self.exports.to_sexp(),
+        ])
+    }
+}
+
+impl FromSexp for Interface {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Interface> {
+        match sexp.as_form()? {
+            ("interface", [_, module, hash, imports, exports]) => Ok(Interface {
+                module: Ident::from_sexp(module)?,
+                hash: hash_from_sexp(hash)?,
+                imports: match imports.as_form()? {
+                    ("imports", imports) => imports.iter()
+                        .map(|import| match import.as_list()? {
+                            [module, fingerprint] =>
+                                Ok((Ident::from_sexp(module)?, hash_from_sexp(fingerprint)?)),
+                            _ => Err(import.error("expected a module and a hash")),
+                        })
+                        .collect::<ParseResult<_>>()?,
+                    _ => return Err(imports.error("expected the imported interfaces")),
+                },
+                exports: TyCtxt::from_sexp(exports)?,
+            }),
+            _ => Err(sexp.error("expected an interface")),
+        }
+    }
+}
+
+fn hash_to_sexp(hash: u64) -> Sexp {
+    Sexp::mk_atom(format!("{:016x}", hash))
+}
+
+fn hash_from_sexp(sexp: &Sexp) -> ParseResult<u64> {
+    u64::from_str_radix(sexp.as_atom()?, 16).map_err(|_| sexp.error("expected a hexadecimal hash"))
+}
+
+/// The 64-bit FNV-1a hash of the source text, which unlike the hashers of
+/// the standard library is the same in every build.
+fn hash(src: &str) -> u64 {
+    src.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
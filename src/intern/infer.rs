@@ -0,0 +1,175 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{subst::Subst, ty_scheme::TyScheme, TyArena, TyId};
+use crate::{
+    error::{TyError, TyResult},
+    expr::{expr::{Expr, ExprKind, Ident}, lit::Lit},
+    ty::{Ty, TyVar},
+};
+
+/// The type context of interned inference.
+type TyCtxt = BTreeMap<Ident, TyScheme>;
+
+/// Algorithm W on interned types. It follows [`crate::infer::InferCtxt`]
+/// step by step and names type variables in the same order, so both infer
+/// the very same types, but it doesn't attach notes to type errors or
+/// report warnings.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, intern::infer::InferCtxt};
+/// // let id = λ x. x in id id
+/// let id = Ident::new("id".to_string());
+/// let expr = Expr::mk_let(
+///     id.clone(),
+///     Expr::mk_abs(Ident::new("x".to_string()), Expr::mk_var(Ident::new("x".to_string()))),
+///     Expr::mk_app(Expr::mk_var(id.clone()), Expr::mk_var(id)),
+/// ).assign_ids();
+///
+/// let ty = InferCtxt::new().infer(&expr).unwrap();
+/// assert_eq!(ty.to_string(), "?3 -> ?3");
+/// ```
+pub struct InferCtxt {
+    arena: TyArena,
+    used_type_var_id: u32,
+}
+
+impl InferCtxt {
+    pub fn new() -> InferCtxt {
+        InferCtxt {
+            arena: TyArena::new(),
+            used_type_var_id: 0,
+        }
+    }
+
+    /// The arena of all types created by inference so far.
+    pub fn arena(&self) -> &TyArena {
+        &self.arena
+    }
+
+    fn new_type_var(&mut self) -> TyId {
+        let id = self.used_type_var_id;
+        self.used_type_var_id += 1;
+        self.arena.var(TyVar::Unknown(id))
+    }
+
+    fn new_num_var(&mut self) -> TyId {
+        let id = self.used_type_var_id;
+        self.used_type_var_id += 1;
+        self.arena.var(TyVar::Num(id))
+    }
+
+    /// Defaults the numeric type variables left in a type to `int`.
+    fn default_nums(&mut self, ty: TyId) -> TyId {
+        let mut defaults = Subst::identity();
+        for var in self.arena.ftv(ty) {
+            if var.is_num() {
+                defaults.insert(var, self.arena.int());
+            }
+        }
+        defaults.apply(&mut self.arena, ty)
+    }
+
+    /// Abstracts a type over all type variables which are free in the type but
+    /// not free in the given type context, except numeric ones.
+    fn generalize(&self, tcx: &TyCtxt, ty: TyId) -> TyScheme {
+        let mut tcx_ftv = BTreeSet::new();
+        for tys in tcx.values() {
+            tys.collect_ftv(&self.arena, &mut tcx_ftv);
+        }
+        let vars = self.arena.ftv(ty)
+            .difference(&tcx_ftv)
+            .filter(|var| !var.is_num())
+            .cloned()
+            .collect();
+        TyScheme::mk_forall(vars, ty)
+    }
+
+    /// Replaces all bound type variables in a type scheme with fresh type
+    /// variables.
+    fn instantiate(&mut self, tys: &TyScheme) -> TyId {
+        if tys.vars.is_empty() {
+            return tys.ty;
+        }
+        let mut names = BTreeMap::new();
+        for var in &tys.vars {
+            let new_ty = self.new_type_var();
+            names.insert(var.clone(), new_ty);
+        }
+        self.arena.rename(tys.ty, &names)
+    }
+
+    fn apply_tcx(&mut self, tcx: &TyCtxt, subst: &Subst) -> TyCtxt {
+        tcx.iter()
+            .map(|(name, tys)| (name.clone(), tys.apply(&mut self.arena, subst)))
+            .collect()
+    }
+
+    fn infer_impl(&mut self, tcx: &TyCtxt, expr: &Expr) -> TyResult<(Subst, TyId)> {
+        match expr.kind {
+            ExprKind::Var(ref name) => match tcx.get(name) {
+                Some(tys) => Ok((Subst::identity(), self.instantiate(tys))),
+                None => Err(TyError::mk_unbound_var(name.clone())),
+            },
+
+            ExprKind::Lit(Lit::Int(_)) => Ok((Subst::identity(), self.new_num_var())),
+            ExprKind::Lit(Lit::Float(_)) => Ok((Subst::identity(), self.arena.float())),
+            ExprKind::Lit(Lit::Bool(_)) => Ok((Subst::identity(), self.arena.bool())),
+            ExprKind::Lit(Lit::Char(_)) => Ok((Subst::identity(), self.arena.char())),
+            ExprKind::Lit(Lit::String(_)) => Ok((Subst::identity(), self.arena.string())),
+            ExprKind::Lit(Lit::Unit) => Ok((Subst::identity(), self.arena.unit())),
+
+            ExprKind::Hole(_) => Ok((Subst::identity(), self.new_type_var())),
+
+            ExprKind::Abs { ref param, ref body } => {
+                let new_ty = self.new_type_var();
+                let mut new_tcx = tcx.clone();
+                new_tcx.insert(param.clone(), TyScheme::mk_forall(vec![], new_ty));
+                let (subst, ty) = self.infer_impl(&new_tcx, body)?;
+                let param_ty = subst.apply(&mut self.arena, new_ty);
+                let ty = self.arena.arrow(param_ty, ty);
+                Ok((subst, ty))
+            },
+
+            ExprKind::App { ref callee, ref arg } => {
+                let new_ty = self.new_type_var();
+                let (s1, ty1) = self.infer_impl(tcx, callee)?;
+                let arg_tcx = self.apply_tcx(tcx, &s1);
+                let (s2, ty2) = self.infer_impl(&arg_tcx, arg)?;
+                let callee_ty = s2.apply(&mut self.arena, ty1);
+                let expected = self.arena.arrow(ty2, new_ty);
+                let s3 = Subst::mgu(&mut self.arena, callee_ty, expected)?;
+                let ty = s3.apply(&mut self.arena, new_ty);
+                let subst = s3.compose(&mut self.arena, &s2);
+                Ok((subst.compose(&mut self.arena, &s1), ty))
+            },
+
+            ExprKind::Let { ref name, ref value, ref body } => {
+                let (s1, t1) = self.infer_impl(tcx, value)?;
+                let mut new_tcx = self.apply_tcx(tcx, &s1);
+                let tys = self.generalize(&new_tcx, t1);
+                new_tcx.insert(name.clone(), tys.apply(&mut self.arena, &s1));
+                let (s2, t2) = self.infer_impl(&new_tcx, body)?;
+                Ok((s2.compose(&mut self.arena, &s1), t2))
+            },
+        }
+    }
+
+    /// Infers the type of an expression, stops at the first type error.
+    pub fn infer(&mut self, expr: &Expr) -> TyResult<Ty> {
+        let (s, t) = self.infer_impl(&TyCtxt::new(), expr)?;
+        let t = s.apply(&mut self.arena, t);
+        let t = self.default_nums(t);
+        Ok(self.arena.to_ty(t))
+    }
+}
+
+impl Default for InferCtxt {
+    fn default() -> InferCtxt {
+        InferCtxt::new()
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Hash-consed types. Every distinct type is stored once in a [`TyArena`] and
+//! referred to by a copyable [`TyId`], so equal types share their structure
+//! and compare by id. Substitutions, type schemes and inference are ported
+//! to interned types in the submodules, and convert to and from [`Ty`] at
+//! the boundary.
+//!
+//! The port is a second engine, not a replacement: [`crate::infer::InferCtxt`]
+//! stays on boxed types, because error recovery, notes, warnings and the
+//! per-expression types that the rest of the crate consumes are only
+//! implemented there. [`infer::InferCtxt`] covers plain Algorithm W, and must
+//! infer the same types and fail with the same errors.
+
+pub mod subst;
+pub mod ty_scheme;
+pub mod infer;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::ty::{Ty, TyVar};
+
+/// A type interned in a [`TyArena`]. Ids are only meaningful in the arena
+/// which created them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TyId(u32);
+
+/// A node of an interned type, its children are ids.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum TyKind {
+    Var(TyVar),
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    Unit,
+    Arrow(TyId, TyId),
+    Error,
+}
+
+/// The storage of interned types. Interning a type that is already in the
+/// arena returns the existing id, no type is ever stored twice.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{intern::TyArena, ty::{Ty, TyVar}};
+/// let mut arena = TyArena::new();
+/// let a = arena.var(TyVar::new("a".to_string()));
+/// let int = arena.int();
+///
+/// // Equal types get the same id.
+/// let id1 = arena.arrow(a, int);
+/// let id2 = arena.arrow(a, int);
+/// assert_eq!(id1, id2);
+/// assert_eq!(arena.to_ty(id1).to_string(), "a -> int");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TyArena {
+    kinds: Vec<TyKind>,
+
+    /// Whether each type has any type variables in it, substitutions skip
+    /// the types that don't.
+    has_vars: Vec<bool>,
+    ids: HashMap<TyKind, TyId>,
+}
+
+impl TyArena {
+    const INT: TyId = TyId(0);
+    const FLOAT: TyId = TyId(1);
+    const BOOL: TyId = TyId(2);
+    const CHAR: TyId = TyId(3);
+    const STRING: TyId = TyId(4);
+    const UNIT: TyId = TyId(5);
+    const ERROR: TyId = TyId(6);
+
+    pub fn new() -> TyArena {
+        let mut arena = TyArena {
+            kinds: Vec::new(),
+            has_vars: Vec::new(),
+            ids: HashMap::new(),
+        };
+        arena.intern(TyKind::Int);
+        arena.intern(TyKind::Float);
+        arena.intern(TyKind::Bool);
+        arena.intern(TyKind::Char);
+        arena.intern(TyKind::String);
+        arena.intern(TyKind::Unit);
+        arena.intern(TyKind::Error);
+        arena
+    }
+
+    /// Returns the id of a type node, adding it to the arena if it's new.
+    pub fn intern(&mut self, kind: TyKind) -> TyId {
+        if let Some(id) = self.ids.get(&kind) {
+            return *id;
+        }
+        let id = TyId(self.kinds.len() as u32);
+        let has_vars = match kind {
+            TyKind::Var(_) => true,
+            TyKind::Int | TyKind::Float | TyKind::Bool | TyKind::Char | TyKind::String
+                | TyKind::Unit | TyKind::Error => false,
+            TyKind::Arrow(param_ty, ret_ty) =>
+                self.has_vars(param_ty) || self.has_vars(ret_ty),
+        };
+        self.kinds.push(kind.clone());
+        self.has_vars.push(has_vars);
+        self.ids.insert(kind, id);
+        id
+    }
+
+    pub fn var(&mut self, var: TyVar) -> TyId {
+        self.intern(TyKind::Var(var))
+    }
+
+    pub fn int(&self) -> TyId {
+        TyArena::INT
+    }
+
+    pub fn float(&self) -> TyId {
+        TyArena::FLOAT
+    }
+
+    pub fn bool(&self) -> TyId {
+        TyArena::BOOL
+    }
+
+    pub fn char(&self) -> TyId {
+        TyArena::CHAR
+    }
+
+    pub fn string(&self) -> TyId {
+        TyArena::STRING
+    }
+
+    pub fn unit(&self) -> TyId {
+        TyArena::UNIT
+    }
+
+    pub fn arrow(&mut self, param: TyId, body: TyId) -> TyId {
+        self.intern(TyKind::Arrow(param, body))
+    }
+
+    pub fn error(&self) -> TyId {
+        TyArena::ERROR
+    }
+
+    pub fn kind(&self, id: TyId) -> &TyKind {
+        &self.kinds[id.0 as usize]
+    }
+
+    pub fn has_vars(&self, id: TyId) -> bool {
+        self.has_vars[id.0 as usize]
+    }
+
+    /// The number of distinct types in the arena.
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// An arena always has the types without children in it.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn from_ty(&mut self, ty: &Ty) -> TyId {
+        match ty {
+            Ty::Var(var) => self.var(var.clone()),
+            Ty::Int => self.int(),
+            Ty::Float => self.float(),
+            Ty::Bool => self.bool(),
+            Ty::Char => self.char(),
+            Ty::String => self.string(),
+            Ty::Unit => self.unit(),
+            Ty::Arrow(param_ty, ret_ty) => {
+                let param_ty = self.from_ty(param_ty);
+                let ret_ty = self.from_ty(ret_ty);
+                self.arrow(param_ty, ret_ty)
+            },
+            Ty::Error => self.error(),
+        }
+    }
+
+    pub fn to_ty(&self, id: TyId) -> Ty {
+        match self.kind(id) {
+            TyKind::Var(var) => Ty::mk_var(var.clone()),
+            TyKind::Int => Ty::mk_int(),
+            TyKind::Float => Ty::mk_float(),
+            TyKind::Bool => Ty::mk_bool(),
+            TyKind::Char => Ty::mk_char(),
+            TyKind::String => Ty::mk_string(),
+            TyKind::Unit => Ty::mk_unit(),
+            TyKind::Arrow(param_ty, ret_ty) =>
+                Ty::mk_arrow(self.to_ty(*param_ty), self.to_ty(*ret_ty)),
+            TyKind::Error => Ty::mk_error(),
+        }
+    }
+
+    pub fn ftv(&self, id: TyId) -> BTreeSet<TyVar> {
+        let mut ftv = BTreeSet::new();
+        self.collect_ftv(id, &mut ftv);
+        ftv
+    }
+
+    /// Adds the free type variables of a type to a set.
+    pub fn collect_ftv(&self, id: TyId, ftv: &mut BTreeSet<TyVar>) {
+        if !self.has_vars(id) {
+            return;
+        }
+        match self.kind(id) {
+            TyKind::Var(var) => {
+                ftv.insert(var.clone());
+            },
+            TyKind::Arrow(param_ty, ret_ty) => {
+                self.collect_ftv(*param_ty, ftv);
+                self.collect_ftv(*ret_ty, ftv);
+            },
+            _ => {},
+        }
+    }
+
+    /// Whether a numeric type variable may be unified with the type, see
+    /// [`Ty::may_be_num`].
+    pub fn may_be_num(&self, id: TyId) -> bool {
+        matches!(self.kind(id), TyKind::Var(_) | TyKind::Int | TyKind::Float | TyKind::Error)
+    }
+
+    /// Checks if a type variable appears in a type.
+    pub fn occurs(&self, var: &TyVar, id: TyId) -> bool {
+        if !self.has_vars(id) {
+            return false;
+        }
+        match self.kind(id) {
+            TyKind::Var(v) => v == var,
+            TyKind::Arrow(param_ty, ret_ty) =>
+                self.occurs(var, *param_ty) || self.occurs(var, *ret_ty),
+            _ => false,
+        }
+    }
+
+    /// Replaces type variables in one pass, like [`Ty::rename`] the
+    /// replacements are not renamed again.
+    pub fn rename(&mut self, id: TyId, names: &BTreeMap<TyVar, TyId>) -> TyId {
+        if !self.has_vars(id) {
+            return id;
+        }
+        match self.kind(id) {
+            TyKind::Var(var) => names.get(var).copied().unwrap_or(id),
+            &TyKind::Arrow(param_ty, ret_ty) => {
+                let new_param_ty = self.rename(param_ty, names);
+                let new_ret_ty = self.rename(ret_ty, names);
+                if new_param_ty == param_ty && new_ret_ty == ret_ty {
+                    id
+                } else {
+                    self.arrow(new_param_ty, new_ret_ty)
+                }
+            },
+            _ => id,
+        }
+    }
+}
+
+impl Default for TyArena {
+    fn default() -> TyArena {
+        TyArena::new()
+    }
+}
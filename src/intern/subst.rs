@@ -0,0 +1,144 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::BTreeMap;
+
+use super::{TyArena, TyId, TyKind};
+use crate::{error::{TyError, TyResult}, ty::{Ty, TyVar}};
+
+/// A substitution of interned types, see [`crate::ty::subst::Subst`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Subst {
+    mapping: BTreeMap<TyVar, TyId>,
+}
+
+impl Subst {
+    pub fn identity() -> Subst {
+        Subst {
+            mapping: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, var: &TyVar) -> Option<TyId> {
+        self.mapping.get(var).copied()
+    }
+
+    pub fn insert(&mut self, var: TyVar, ty: TyId) {
+        self.mapping.insert(var, ty);
+    }
+
+    pub fn remove(&mut self, var: &TyVar) -> Option<TyId> {
+        self.mapping.remove(var)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mapping.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&TyVar, &TyId)> {
+        self.mapping.iter()
+    }
+
+    /// Applies the substitution to a type. Types which the substitution
+    /// doesn't change keep their ids, nothing is interned for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{intern::{TyArena, subst::Subst}, ty::TyVar};
+    /// let mut arena = TyArena::new();
+    /// let a = TyVar::new("a".to_string());
+    /// let b = arena.var(TyVar::new("b".to_string()));
+    /// let (int, bool) = (arena.int(), arena.bool());
+    /// let ty = arena.arrow(b, int);
+    ///
+    /// let mut subst = Subst::identity();
+    /// subst.insert(a, bool);
+    /// let len = arena.len();
+    /// assert_eq!(subst.apply(&mut arena, ty), ty);
+    /// assert_eq!(arena.len(), len);
+    /// ```
+    pub fn apply(&self, arena: &mut TyArena, ty: TyId) -> TyId {
+        if self.mapping.is_empty() || !arena.has_vars(ty) {
+            return ty;
+        }
+        match arena.kind(ty) {
+            TyKind::Var(var) => match self.get(var) {
+                Some(found_ty) if found_ty != ty => self.apply(arena, found_ty),
+                _ => ty,
+            },
+            &TyKind::Arrow(param_ty, ret_ty) => {
+                let new_param_ty = self.apply(arena, param_ty);
+                let new_ret_ty = self.apply(arena, ret_ty);
+                if new_param_ty == param_ty && new_ret_ty == ret_ty {
+                    ty
+                } else {
+                    arena.arrow(new_param_ty, new_ret_ty)
+                }
+            },
+            _ => ty,
+        }
+    }
+
+    /// Composes another substitution with this one, `self.compose(other)` is
+    /// `self + other` as in [`crate::ty::subst::Subst::compose`].
+    pub fn compose(&self, arena: &mut TyArena, other: &Subst) -> Subst {
+        if other.is_empty() {
+            return self.clone();
+        }
+        let mut subst = self.clone();
+        for (var, ty) in other.iter() {
+            subst.insert(var.clone(), self.apply(arena, *ty));
+        }
+        subst
+    }
+
+    /// Binds a type variable to a type, with the occurs check.
+    pub fn bind(arena: &TyArena, var: TyVar, ty: TyId) -> TyResult<Subst> {
+        match arena.kind(ty) {
+            TyKind::Var(v) if v == &var =>
+                Ok(Subst::identity()),
+            _ if arena.occurs(&var, ty) =>
+                Err(TyError::mk_infinite_type(var, arena.to_ty(ty))),
+            _ => {
+                let mut subst = Subst::identity();
+                subst.insert(var, ty);
+                Ok(subst)
+            },
+        }
+    }
+
+    /// Finds the most general unifier of two types. The same types have the
+    /// same id, so they are unified at once. Numeric type variables are
+    /// unified like in [`crate::ty::subst::Subst::mgu`].
+    pub fn mgu(arena: &mut TyArena, ty1: TyId, ty2: TyId) -> TyResult<Subst> {
+        if ty1 == ty2 {
+            return Ok(Subst::identity());
+        }
+        match (arena.kind(ty1), arena.kind(ty2)) {
+            (&TyKind::Arrow(p1, r1), &TyKind::Arrow(p2, r2)) => {
+                let s1 = Subst::mgu(arena, p1, p2)?;
+                let r1 = s1.apply(arena, r1);
+                let r2 = s1.apply(arena, r2);
+                let s2 = Subst::mgu(arena, r1, r2)?;
+                Ok(s1.compose(arena, &s2))
+            },
+            (TyKind::Var(var), TyKind::Var(TyVar::Num(_))) =>
+                Subst::bind(arena, var.clone(), ty2),
+            (TyKind::Var(TyVar::Num(_)), TyKind::Var(var)) =>
+                Subst::bind(arena, var.clone(), ty1),
+            (TyKind::Var(TyVar::Num(_)), _) if !arena.may_be_num(ty2) =>
+                Err(TyError::mk_mismatch(Ty::mk_int(), arena.to_ty(ty2).default_nums())),
+            (_, TyKind::Var(TyVar::Num(_))) if !arena.may_be_num(ty1) =>
+                Err(TyError::mk_mismatch(arena.to_ty(ty1).default_nums(), Ty::mk_int())),
+            (TyKind::Var(var), _) =>
+                Subst::bind(arena, var.clone(), ty2),
+            (_, TyKind::Var(var)) =>
+                Subst::bind(arena, var.clone(), ty1),
+            (TyKind::Error, _) | (_, TyKind::Error) =>
+                Ok(Subst::identity()),
+            _ =>
+                Err(TyError::mk_mismatch(arena.to_ty(ty1), arena.to_ty(ty2))),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::BTreeSet;
+
+use super::{subst::Subst, TyArena, TyId};
+use crate::ty::{TyVar, ty_scheme};
+
+/// A type scheme of an interned type, see [`ty_scheme::TyScheme`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TyScheme {
+    pub vars: Vec<TyVar>,
+    pub ty: TyId,
+}
+
+impl TyScheme {
+    pub fn mk_forall(vars: Vec<TyVar>, ty: TyId) -> TyScheme {
+        TyScheme { vars, ty }
+    }
+
+    /// Adds the free type variables of the scheme to a set.
+    pub fn collect_ftv(&self, arena: &TyArena, ftv: &mut BTreeSet<TyVar>) {
+        let mut ty_ftv = arena.ftv(self.ty);
+        for var in &self.vars {
+            ty_ftv.remove(var);
+        }
+        ftv.extend(ty_ftv);
+    }
+
+    pub fn ftv(&self, arena: &TyArena) -> BTreeSet<TyVar> {
+        let mut ftv = BTreeSet::new();
+        self.collect_ftv(arena, &mut ftv);
+        ftv
+    }
+
+    /// Applies a substitution to the free type variables of the scheme.
+    pub fn apply(&self, arena: &mut TyArena, subst: &Subst) -> TyScheme {
+        // The substitution is only copied when it binds a bound variable.
+        let ty = if self.vars.iter().any(|var| subst.get(var).is_some()) {
+            let subst = self.vars
+                .iter()
+                .fold(subst.clone(), |mut sub, var| { sub.remove(var); sub });
+            subst.apply(arena, self.ty)
+        } else {
+            subst.apply(arena, self.ty)
+        };
+        TyScheme::mk_forall(self.vars.clone(), ty)
+    }
+
+    pub fn from_scheme(arena: &mut TyArena, tys: &ty_scheme::TyScheme) -> TyScheme {
+        TyScheme::mk_forall(tys.vars.clone(), arena.from_ty(&tys.ty))
+    }
+
+    pub fn to_scheme(&self, arena: &TyArena) -> ty_scheme::TyScheme {
+        ty_scheme::TyScheme::mk_forall(self.vars.clone(), arena.to_ty(self.ty))
+    }
+}
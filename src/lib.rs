@@ -5,3 +5,19 @@ pub mod expr;
 pub mod ty;
 pub mod infer;
 pub mod error;
+pub mod derivation;
+pub mod slice;
+pub mod hole;
+pub mod syntax;
+pub mod program;
+pub mod eval;
+pub mod gen;
+pub mod pretty;
+pub mod system_f;
+pub mod intern;
+pub mod visit;
+pub mod resolve;
+pub mod reference;
+pub mod prelude;
+pub mod module;
+pub mod interface;
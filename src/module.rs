@@ -0,0 +1,185 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Modules, programs that import the bindings exported by other modules.
+//!
+//! A module may have a signature, the declared type schemes of the bindings
+//! it exports. A module is checked against the interfaces of the modules it
+//! imports, which are their signatures if they have one, so it can be checked
+//! without the source of its dependencies.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{ModuleError, ModuleResult},
+    expr::expr::Ident,
+    program::{IncrementalCtxt, Program},
+    ty::ty_ctxt::TyCtxt,
+};
+
+/// A module, see [`crate::syntax::parser::parse_module`] for its syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub name: Ident,
+
+    /// The modules whose exports are in scope in this module. If two of them
+    /// export the same name, the later one is in scope, and the bindings of
+    /// the module shadow all of them.
+    pub imports: Vec<Ident>,
+
+    pub program: Program,
+
+    /// The exported bindings with their declared schemes. Without a
+    /// signature, every binding is exported with its inferred scheme.
+    pub signature: Option<TyCtxt>,
+}
+
+impl Module {
+    pub fn new(name: Ident, imports: Vec<Ident>, program: Program) -> Module {
+        Module { name, imports, program, signature: None }
+    }
+
+    pub fn with_signature(mut self, signature: TyCtxt) -> Module {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Checks the module against the interfaces of the modules it imports,
+    /// and returns its own interface, the schemes of the bindings it exports.
+    /// The inferred scheme of every binding in the signature must be at least
+    /// as general as the declared one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use type_infer_rs::{expr::expr::Ident, syntax::parser::{parse_module, parse_signature}};
+    /// let name = |name: &str| Ident::new(name.to_string());
+    ///
+    /// // Only the signature of `util` is needed to check `main`.
+    /// let util = parse_signature("val const : forall a b . a -> b -> a;").unwrap();
+    /// let main = parse_module(name("main"), "
+    ///     import util;
+    ///     let f = const 1;
+    /// ").unwrap();
+    /// let interface = main.check(&BTreeMap::from([(name("util"), util)])).unwrap();
+    /// assert_eq!(interface.get(&name("f")).unwrap().to_string(), "forall a . a -> int");
+    ///
+    /// // `λ x. x` is more general than declared, `λ x. 1` is not.
+    /// let sig = parse_signature("val f : bool -> bool;").unwrap();
+    /// let module = |src| parse_module(name("m"), src).unwrap().with_signature(sig.clone());
+    /// assert!(module("let f = λ x. x;").check(&BTreeMap::new()).is_ok());
+    /// assert!(module("let f = λ x. 1;").check(&BTreeMap::new()).is_err());
+    /// ```
+    pub fn check(&self, interfaces: &BTreeMap<Ident, TyCtxt>) -> ModuleResult<TyCtxt> {
+        let mut globals = TyCtxt::new();
+        for import in &self.imports {
+            let interface = interfaces.get(import)
+                .ok_or_else(|| ModuleError::UnknownModule(import.clone()))?;
+            for (name, tys) in interface.iter() {
+                globals.insert(name.clone(), tys.clone());
+            }
+        }
+
+        let checked = IncrementalCtxt::with_globals(globals).check(&self.program);
+        let mut exports = TyCtxt::new();
+        for decl in &self.program.decls {
+            match &checked.schemes[&decl.name] {
+                Ok(tys) => exports.insert(decl.name.clone(), tys.normalize()),
+                Err(err) => return Err(ModuleError::Binding(decl.name.clone(), err.clone())),
+            }
+        }
+
+        let Some(signature) = &self.signature else {
+            return Ok(exports);
+        };
+        for (name, declared) in signature.iter() {
+            let inferred = exports.get(name)
+                .ok_or_else(|| ModuleError::MissingBinding(name.clone()))?;
+            if !declared.is_instance_of(inferred) {
+                return Err(ModuleError::NotGeneral {
+                    name: name.clone(),
+                    declared: declared.clone(),
+                    inferred: inferred.clone(),
+                });
+            }
+        }
+        Ok(signature.clone())
+    }
+}
+
+/// Checks modules that import each other, and returns the interface of every
+/// module or the error it failed with.
+///
+/// A module that imports a module with a signature is checked against the
+/// signature, even if the imported module fails to check. Only modules
+/// without a signature have to be checked before the modules that import
+/// them, and they must not import each other in a cycle.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::expr::Ident, module::check_modules};
+/// # use type_infer_rs::syntax::parser::{parse_module, parse_signature};
+/// let name = |name: &str| Ident::new(name.to_string());
+/// let modules = [
+///     parse_module(name("main"), "import id; let n = id 1;").unwrap(),
+///     parse_module(name("id"), "let id = λ x. x;").unwrap(),
+/// ];
+/// let checked = check_modules(&modules);
+/// assert_eq!(checked[&name("main")].as_ref().unwrap().get(&name("n")).unwrap().to_string(), "int");
+/// ```
+pub fn check_modules(modules: &[Module]) -> BTreeMap<Ident, ModuleResult<TyCtxt>> {
+    let mut checker = Checker {
+        modules: modules.iter().map(|module| (&module.name, module)).collect(),
+        visiting: Vec::new(),
+        checked: BTreeMap::new(),
+    };
+    for module in modules {
+        checker.check(&module.name);
+    }
+    checker.checked
+}
+
+struct Checker<'a> {
+    modules: BTreeMap<&'a Ident, &'a Module>,
+
+    /// The modules being checked, each imports the next one.
+    visiting: Vec<Ident>,
+
+    checked: BTreeMap<Ident, ModuleResult<TyCtxt>>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, name: &Ident) {
+        if self.checked.contains_key(name) {
+            return;
+        }
+        let module = self.modules[name];
+        self.visiting.push(name.clone());
+        let result = module.imports.iter()
+            .map(|import| Ok((import.clone(), self.interface(import)?)))
+            .collect::<ModuleResult<_>>()
+            .and_then(|interfaces| module.check(&interfaces));
+        self.visiting.pop();
+        self.checked.insert(name.clone(), result);
+    }
+
+    /// The interface of an imported module, the one its importers see.
+    fn interface(&mut self, name: &Ident) -> ModuleResult<TyCtxt> {
+        let Some(module) = self.modules.get(name) else {
+            return Err(ModuleError::UnknownModule(name.clone()));
+        };
+        if let Some(signature) = &module.signature {
+            return Ok(signature.clone());
+        }
+        if let Some(start) = self.visiting.iter().position(|visiting| visiting == name) {
+            let mut cycle = self.visiting[start..].to_vec();
+            cycle.push(name.clone());
+            return Err(ModuleError::ImportCycle(cycle));
+        }
+        self.check(name);
+        self.checked[name].clone()
+            .map_err(|_| ModuleError::DependencyFailed(name.clone()))
+    }
+}
@@ -0,0 +1,43 @@
+-- The prelude, in scope in every program checked against it.
+--
+-- The language has no data types, so they are Church encoded: a value is its
+-- own fold. There is no conditional on `bool` either, so predicates return
+-- the Church booleans `ctrue` and `cfalse`.
+
+-- Functions
+let id = λ x. x;
+let const = λ x. λ y. x;
+let flip = λ f. λ x. λ y. f y x;
+let compose = λ f. λ g. λ x. f (g x);
+
+-- Booleans
+let ctrue = λ t. λ f. t;
+let cfalse = λ t. λ f. f;
+let not = λ b. λ t. λ f. b f t;
+
+-- Pairs
+let pair = λ a. λ b. λ k. k a b;
+let fst = λ p. p (λ a. λ b. a);
+let snd = λ p. p (λ a. λ b. b);
+
+-- Options
+let none = λ n. λ s. n;
+let some = λ x. λ n. λ s. s x;
+let option = λ n. λ s. λ o. o n s;
+let map_option = λ f. λ o. λ n. λ s. o n (λ x. s (f x));
+
+-- Lists
+let nil = λ c. λ n. n;
+let cons = λ x. λ xs. λ c. λ n. c x (xs c n);
+let fold = λ f. λ z. λ xs. xs f z;
+let map = λ f. λ xs. λ c. λ n. xs (λ x. c (f x)) n;
+let filter = λ p. λ xs. λ c. λ n. xs (λ x. λ acc. p x (c x acc) acc) n;
+let append = λ xs. λ ys. λ c. λ n. xs c (ys c n);
+
+-- Strings and floats, on top of the primitives `concat`, `fadd`, `fsub`,
+-- `fmul` and `fdiv`. The prelude is evaluated without its types, so float
+-- constants are written as float literals, not integers.
+let concat_all = fold concat "";
+let sum = fold fadd 0.0;
+let product = fold fmul 1.0;
+let negate = fsub 0.0;
@@ -0,0 +1,103 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! The prelude, a program of common functions bundled with the library, on
+//! top of the primitive functions in [`Prim`].
+//!
+//! Its type schemes are bundled too, as the interface file [`INTERFACE`], so
+//! they are loaded rather than inferred. The prelude is only inferred if the
+//! interface file is stale, and either way only the first time it is used,
+//! later uses share the type schemes and the values.
+
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use crate::{
+    eval::{eval_in, Env, Prim, Value},
+    expr::expr::Ident,
+    interface::Interface,
+    program::IncrementalCtxt,
+    syntax::parser::parse_program,
+    ty::{ty_ctxt::TyCtxt, ty_scheme::TyScheme},
+};
+
+/// The source text of the prelude.
+pub const SOURCE: &str = include_str!("prelude.ml");
+
+/// The interface file of the prelude, written by [`interface`]. The test
+/// `bundled_interface_is_up_to_date` writes it again when `UPDATE_PRELUDE`
+/// is set.
+pub const INTERFACE: &str = include_str!("prelude.tyi");
+
+/// The type schemes of the primitives, which the prelude is checked against.
+fn prims() -> TyCtxt {
+    let mut tcx = TyCtxt::new();
+    for prim in Prim::ALL {
+        tcx.insert(prim.name(), TyScheme::mk_forall(Vec::new(), prim.ty()));
+    }
+    tcx
+}
+
+/// Infers the prelude, and returns its interface.
+pub fn interface() -> Interface {
+    let program = parse_program(SOURCE).expect("the prelude parses");
+    let mut exports = TyCtxt::new();
+    for (name, tys) in IncrementalCtxt::with_globals(prims()).check(&program).schemes {
+        let tys = tys.unwrap_or_else(|err| panic!("`{}` in the prelude: {}", name, err));
+        exports.insert(name, tys);
+    }
+    Interface::new(Ident::new("prelude".to_string()), SOURCE, exports)
+}
+
+/// The type schemes of the primitives and the bindings of the prelude.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{infer::InferCtxt, prelude, syntax::parser::parse};
+/// let expr = parse("fst (pair 1 true)").unwrap().expr;
+/// let ty = InferCtxt::new().infer_in(prelude::tcx(), &expr).unwrap();
+/// assert_eq!(ty.to_string(), "int");
+/// ```
+pub fn tcx() -> &'static TyCtxt {
+    static TCX: OnceLock<TyCtxt> = OnceLock::new();
+    TCX.get_or_init(|| {
+        let exports = Interface::read(INTERFACE, SOURCE, &BTreeMap::new())
+            .unwrap_or_else(|_| interface())
+            .exports;
+        let mut tcx = prims();
+        for (name, tys) in exports.iter() {
+            tcx.insert(name.clone(), tys.clone());
+        }
+        tcx
+    })
+}
+
+/// The values of the primitives and the bindings of the prelude, for
+/// evaluating programs checked against [`tcx`].
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{eval::{eval_in, Value}, prelude, syntax::parser::parse};
+/// let expr = parse(r#"concat_all (cons "a" (cons "b" nil))"#).unwrap().expr;
+/// assert_eq!(eval_in(prelude::env(), &expr), Ok(Value::String("ab".to_string())));
+/// ```
+pub fn env() -> &'static Env {
+    static ENV: OnceLock<Env> = OnceLock::new();
+    ENV.get_or_init(|| {
+        let mut env = Env::new();
+        for prim in Prim::ALL {
+            env.insert(prim.name(), Value::Prim { prim, args: Vec::new() });
+        }
+        let program = parse_program(SOURCE).expect("the prelude parses");
+        // The bindings of the prelude are not recursive, every group is a
+        // single binding.
+        for group in program.groups() {
+            let decl = &program.decls[group[0]];
+            let value = eval_in(&env, &decl.expr)
+                .unwrap_or_else(|err| panic!("`{}` in the prelude: {}", decl.name, err));
+            env.insert(decl.name.clone(), value);
+        }
+        env
+    })
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+/// A document with optional line breaks, laid out to fit a page width, after
+/// Wadler's "A prettier printer".
+#[derive(Debug, Clone, PartialEq)]
+pub enum Doc {
+    Nil,
+    Text(String),
+
+    /// A space, or a line break followed by the current indentation.
+    Line,
+    Cat(Box<Doc>, Box<Doc>),
+
+    /// Indents the line breaks of the inner document.
+    Nest(usize, Box<Doc>),
+
+    /// All line breaks of the inner document are spaces if it fits on the
+    /// rest of the line, or all are line breaks.
+    Group(Box<Doc>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+impl Doc {
+    pub fn mk_nil() -> Doc {
+        Doc::Nil
+    }
+
+    pub fn mk_text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    pub fn mk_line() -> Doc {
+        Doc::Line
+    }
+
+    pub fn append(self, other: Doc) -> Doc {
+        Doc::Cat(Box::new(self), Box::new(other))
+    }
+
+    pub fn nest(self, indent: usize) -> Doc {
+        Doc::Nest(indent, Box::new(self))
+    }
+
+    pub fn group(self) -> Doc {
+        Doc::Group(Box::new(self))
+    }
+
+    /// Lays out the document, breaking the lines of the outermost groups that
+    /// don't fit the width.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::pretty::doc::Doc;
+    /// let doc = Doc::mk_text("let x =")
+    ///     .append(Doc::mk_line().append(Doc::mk_text("42")).nest(2))
+    ///     .group();
+    ///
+    /// assert_eq!(doc.render(80), "let x = 42");
+    /// assert_eq!(doc.render(8), "let x =\n  42");
+    /// ```
+    pub fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        let mut column = 0;
+        let mut stack = vec![(0, Mode::Break, self)];
+
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Nil => {},
+                Doc::Text(text) => {
+                    out.push_str(text);
+                    column += text.chars().count();
+                },
+                Doc::Line if mode == Mode::Flat => {
+                    out.push(' ');
+                    column += 1;
+                },
+                Doc::Line => {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent));
+                    column = indent;
+                },
+                Doc::Cat(first, second) => {
+                    stack.push((indent, mode, second));
+                    stack.push((indent, mode, first));
+                },
+                Doc::Nest(more, doc) => stack.push((indent + more, mode, doc)),
+                Doc::Group(doc) => {
+                    let remaining = width.min(isize::MAX as usize) as isize - column as isize;
+                    let mode = if mode == Mode::Flat || fits(remaining, doc, &stack) {
+                        Mode::Flat
+                    } else {
+                        Mode::Break
+                    };
+                    stack.push((indent, mode, doc));
+                },
+            }
+        }
+        out
+    }
+}
+
+/// Checks whether a flat document, and what follows it up to the next line
+/// break, fits in the remaining width.
+fn fits(mut remaining: isize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut rest = rest.iter().rev().map(|(_, mode, doc)| (*mode, *doc));
+    let mut stack = vec![(Mode::Flat, doc)];
+
+    while remaining >= 0 {
+        let Some((mode, doc)) = stack.pop().or_else(|| rest.next()) else {
+            return true;
+        };
+        match doc {
+            Doc::Nil => {},
+            Doc::Text(text) => remaining -= text.chars().count() as isize,
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::Line => return true,
+            Doc::Cat(first, second) => {
+                stack.push((mode, second));
+                stack.push((mode, first));
+            },
+            Doc::Nest(_, doc) | Doc::Group(doc) => stack.push((mode, doc)),
+        }
+    }
+    false
+}
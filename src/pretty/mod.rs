@@ -0,0 +1,250 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! A configurable pretty printer for types, type schemes and expressions.
+
+pub mod doc;
+
+use std::collections::BTreeMap;
+
+use self::doc::Doc;
+use crate::{
+    expr::expr::{Expr, ExprKind},
+    ty::{Ty, TyVar, ty_scheme::TyScheme},
+};
+
+/// The settings of the pretty printer.
+///
+/// Unlike `Display`, it breaks long lines to fit a width, renames type
+/// variables to `'a`, `'b`, ... in the order they appear, and only puts the
+/// parentheses that are needed.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{pretty::PrettyCtxt, ty::{Ty, TyVar, ty_scheme::TyScheme}};
+/// // forall ?4 ?7 . (?7 -> ?4) -> ?7 -> ?4
+/// let tys = TyScheme::mk_forall(
+///     vec![TyVar::Unknown(4), TyVar::Unknown(7)],
+///     Ty::mk_arrow(
+///         Ty::mk_arrow(Ty::mk_var(TyVar::Unknown(7)), Ty::mk_var(TyVar::Unknown(4))),
+///         Ty::mk_arrow(Ty::mk_var(TyVar::Unknown(7)), Ty::mk_var(TyVar::Unknown(4))),
+///     ),
+/// );
+///
+/// assert_eq!(PrettyCtxt::new().scheme(&tys), "∀ 'a 'b. ('a → 'b) → 'a → 'b");
+/// assert_eq!(
+///     PrettyCtxt::new().with_unicode(false).scheme(&tys),
+///     "forall 'a 'b. ('a -> 'b) -> 'a -> 'b",
+/// );
+/// assert_eq!(
+///     PrettyCtxt::new().with_width(16).scheme(&tys),
+///     "∀ 'a 'b.\n  ('a → 'b) →\n  'a →\n  'b",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrettyCtxt {
+    width: usize,
+    unicode: bool,
+    renaming: bool,
+}
+
+/// The precedence of the position an expression is printed in.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Prec {
+    /// Anything fits, λ and `let` extend as far to the right as possible.
+    Top,
+    Callee,
+    Arg,
+}
+
+impl PrettyCtxt {
+    /// The default settings, a width of 80 with Unicode and renaming.
+    pub fn new() -> PrettyCtxt {
+        PrettyCtxt { width: 80, unicode: true, renaming: true }
+    }
+
+    pub fn with_width(mut self, width: usize) -> PrettyCtxt {
+        self.width = width;
+        self
+    }
+
+    /// Uses `∀`, `→` and `λ`, or `forall`, `->` and `\`.
+    pub fn with_unicode(mut self, unicode: bool) -> PrettyCtxt {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Renames type variables to `'a`, `'b`, ..., or keeps their names.
+    pub fn with_renaming(mut self, renaming: bool) -> PrettyCtxt {
+        self.renaming = renaming;
+        self
+    }
+
+    pub fn ty(&self, ty: &Ty) -> String {
+        let names = self.names(ty, &[]);
+        self.ty_doc(ty, &names).group().render(self.width)
+    }
+
+    pub fn scheme(&self, tys: &TyScheme) -> String {
+        let names = self.names(&tys.ty, &tys.vars);
+        self.scheme_doc(tys, &names).render(self.width)
+    }
+
+    /// Prints an expression so that parsing it gives the same expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::{pretty::PrettyCtxt, syntax::parser::parse};
+    /// let expr = parse("let f = (λ x. x) in ((f f) (λ y. y))").unwrap().expr;
+    ///
+    /// assert_eq!(PrettyCtxt::new().expr(&expr), "let f = λ x. x in f f λ y. y");
+    /// assert_eq!(PrettyCtxt::new().with_unicode(false).expr(&expr), "let f = \\x. x in f f \\y. y");
+    /// ```
+    pub fn expr(&self, expr: &Expr) -> String {
+        self.expr_doc(expr, Prec::Top, true).group().render(self.width)
+    }
+
+    /// Names the variables in the order they appear, bound variables that
+    /// don't appear come last.
+    fn names(&self, ty: &Ty, bound: &[TyVar]) -> BTreeMap<TyVar, (usize, String)> {
+        fn collect(ty: &Ty, order: &mut Vec<TyVar>) {
+            match ty {
+                Ty::Var(var) if !order.contains(var) => order.push(var.clone()),
+                Ty::Arrow(param_ty, ret_ty) => {
+                    collect(param_ty, order);
+                    collect(ret_ty, order);
+                },
+                _ => {},
+            }
+        }
+
+        let mut order = Vec::new();
+        collect(ty, &mut order);
+        order.extend(bound.iter().filter(|var| !order.contains(var)).cloned().collect::<Vec<_>>());
+        order.into_iter()
+            .enumerate()
+            .map(|(i, var)| {
+                let name = if !self.renaming {
+                    var.to_string()
+                } else if i < 26 {
+                    format!("'{}", (b'a' + i as u8) as char)
+                } else {
+                    format!("'{}{}", (b'a' + (i % 26) as u8) as char, i / 26)
+                };
+                (var, (i, name))
+            })
+            .collect()
+    }
+
+    fn arrow(&self) -> &'static str {
+        if self.unicode { " →" } else { " ->" }
+    }
+
+    fn ty_doc(&self, ty: &Ty, names: &BTreeMap<TyVar, (usize, String)>) -> Doc {
+        match ty {
+            Ty::Var(var) => Doc::mk_text(names[var].1.clone()),
+            Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String | Ty::Unit | Ty::Error =>
+                Doc::mk_text(ty.to_string()),
+            Ty::Arrow(_, _) => {
+                // Arrows associate to the right, a chain is broken at every
+                // arrow or none.
+                let mut doc = Doc::mk_nil();
+                let mut ty = ty;
+                while let Ty::Arrow(param_ty, ret_ty) = ty {
+                    let param = match **param_ty {
+                        Ty::Arrow(_, _) => parens(self.ty_doc(param_ty, names).group()),
+                        _ => self.ty_doc(param_ty, names),
+                    };
+                    doc = doc
+                        .append(param)
+                        .append(Doc::mk_text(self.arrow()))
+                        .append(Doc::mk_line());
+                    ty = ret_ty;
+                }
+                doc.append(self.ty_doc(ty, names))
+            },
+        }
+    }
+
+    fn scheme_doc(&self, tys: &TyScheme, names: &BTreeMap<TyVar, (usize, String)>) -> Doc {
+        let ty = self.ty_doc(&tys.ty, names);
+        if tys.vars.is_empty() {
+            return ty.group();
+        }
+        // With renaming, the variables are listed as `'a 'b 'c`.
+        let mut vars = tys.vars.clone();
+        if self.renaming {
+            vars.sort_by_key(|var| names[var].0);
+        }
+        let vars = vars.iter()
+            .map(|var| names[var].1.clone())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let forall = if self.unicode { "∀" } else { "forall" };
+        Doc::mk_text(format!("{} {}.", forall, vars))
+            .append(Doc::mk_line().append(ty).nest(2))
+            .group()
+    }
+
+    /// `tail` tells whether nothing follows the expression up to the end of
+    /// its enclosing parentheses, λ or `let`.
+    fn expr_doc(&self, expr: &Expr, prec: Prec, tail: bool) -> Doc {
+        match &expr.kind {
+            ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) =>
+                Doc::mk_text(expr.to_string()),
+            ExprKind::App { .. } if prec == Prec::Arg =>
+                parens(self.expr_doc(expr, Prec::Top, true)),
+            ExprKind::App { .. } => {
+                let mut args = Vec::new();
+                let mut callee = expr;
+                while let ExprKind::App { callee: inner, arg } = &callee.kind {
+                    args.push(arg);
+                    callee = inner;
+                }
+                let last = args.len() - 1;
+                let args = args.iter().rev().enumerate().fold(Doc::mk_nil(), |doc, (i, arg)| {
+                    doc.append(Doc::mk_line())
+                        .append(self.expr_doc(arg, Prec::Arg, tail && i == last))
+                });
+                self.expr_doc(callee, Prec::Callee, false)
+                    .append(args.nest(2))
+                    .group()
+            },
+            ExprKind::Abs { .. } | ExprKind::Let { .. } if prec != Prec::Top && !tail =>
+                parens(self.expr_doc(expr, Prec::Top, true)),
+            ExprKind::Abs { param, body } => {
+                let lambda = if self.unicode {
+                    format!("λ {}.", param)
+                } else {
+                    format!("\\{}.", param)
+                };
+                Doc::mk_text(lambda)
+                    .append(Doc::mk_line().append(self.expr_doc(body, Prec::Top, true)).nest(2))
+                    .group()
+            },
+            ExprKind::Let { name, value, body } => {
+                let binding = Doc::mk_text(format!("let {} =", name))
+                    .append(Doc::mk_line().append(self.expr_doc(value, Prec::Top, true)).nest(2))
+                    .append(Doc::mk_line())
+                    .append(Doc::mk_text("in"))
+                    .group();
+                binding
+                    .append(Doc::mk_line())
+                    .append(self.expr_doc(body, Prec::Top, true))
+                    .group()
+            },
+        }
+    }
+}
+
+impl Default for PrettyCtxt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parens(doc: Doc) -> Doc {
+    Doc::mk_text("(").append(doc).append(Doc::mk_text(")"))
+}
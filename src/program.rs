@@ -0,0 +1,482 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Programs as sequences of top-level bindings, and their incremental type
+//! checking.
+//!
+//! Top-level bindings may refer to each other in any order, bindings that
+//! refer to each other in a cycle are mutually recursive and form a binding
+//! group, which is inferred as a whole.
+
+use std::{collections::{BTreeMap, BTreeSet, VecDeque}, sync::{Condvar, Mutex}, thread};
+
+use crate::{
+    error::TyResult,
+    expr::expr::{Expr, ExprKind, Ident},
+    infer::InferCtxt,
+    ty::{Ty, ty_ctxt::TyCtxt, ty_scheme::TyScheme},
+};
+
+/// A top-level binding `let name = expr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decl {
+    pub name: Ident,
+    pub expr: Expr,
+}
+
+impl Decl {
+    pub fn new(name: Ident, expr: Expr) -> Decl {
+        Decl { name, expr }
+    }
+}
+
+/// A sequence of top-level bindings. If two bindings have the same name, the
+/// later one is the one other bindings refer to.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub decls: Vec<Decl>,
+}
+
+impl Program {
+    pub fn new(decls: Vec<Decl>) -> Program {
+        Program { decls }
+    }
+
+    /// The indices of the bindings each binding refers to.
+    pub fn deps(&self) -> Vec<BTreeSet<usize>> {
+        let indices = self.decls.iter()
+            .enumerate()
+            .map(|(i, decl)| (&decl.name, i))
+            .collect::<BTreeMap<_, _>>();
+        self.decls.iter()
+            .map(|decl| {
+                let mut vars = BTreeSet::new();
+                free_vars(&decl.expr, &mut Vec::new(), &mut vars);
+                vars.iter().filter_map(|var| indices.get(var).copied()).collect()
+            })
+            .collect()
+    }
+
+    /// Whether each binding is shadowed by a later binding with the same name,
+    /// so that no other binding can refer to it.
+    fn shadowed(&self) -> Vec<bool> {
+        let mut names = BTreeSet::new();
+        let mut shadowed = self.decls.iter()
+            .rev()
+            .map(|decl| !names.insert(&decl.name))
+            .collect::<Vec<_>>();
+        shadowed.reverse();
+        shadowed
+    }
+
+    /// Splits the bindings into groups of mutually recursive bindings, the
+    /// strongly connected components of the dependency graph. A group comes
+    /// after all the groups it depends on, and the indices in a group are in
+    /// ascending order.
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut tarjan = Tarjan {
+            deps: self.deps(),
+            index: vec![None; self.decls.len()],
+            low_link: vec![0; self.decls.len()],
+            on_stack: vec![false; self.decls.len()],
+            stack: Vec::new(),
+            next_index: 0,
+            groups: Vec::new(),
+        };
+        for v in 0..self.decls.len() {
+            if tarjan.index[v].is_none() {
+                tarjan.connect(v);
+            }
+        }
+        tarjan.groups
+    }
+}
+
+/// Tarjan's strongly connected components algorithm, which finds components
+/// in reverse topological order, that is, dependencies first.
+struct Tarjan {
+    deps: Vec<BTreeSet<usize>>,
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    groups: Vec<Vec<usize>>,
+}
+
+impl Tarjan {
+    fn connect(&mut self, v: usize) {
+        self.index[v] = Some(self.next_index);
+        self.low_link[v] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+
+        for w in self.deps[v].clone() {
+            match self.index[w] {
+                None => {
+                    self.connect(w);
+                    self.low_link[v] = self.low_link[v].min(self.low_link[w]);
+                },
+                Some(index) if self.on_stack[w] =>
+                    self.low_link[v] = self.low_link[v].min(index),
+                Some(_) => {},
+            }
+        }
+
+        if Some(self.low_link[v]) == self.index[v] {
+            let mut group = Vec::new();
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                group.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            group.sort();
+            self.groups.push(group);
+        }
+    }
+}
+
+/// Collects the variables that occur free in an expression.
+fn free_vars(expr: &Expr, bound: &mut Vec<Ident>, vars: &mut BTreeSet<Ident>) {
+    match &expr.kind {
+        ExprKind::Var(name) => {
+            if !bound.contains(name) {
+                vars.insert(name.clone());
+            }
+        },
+        ExprKind::Lit(_) | ExprKind::Hole(_) => {},
+        ExprKind::App { callee, arg } => {
+            free_vars(callee, bound, vars);
+            free_vars(arg, bound, vars);
+        },
+        ExprKind::Abs { param, body } => {
+            bound.push(param.clone());
+            free_vars(body, bound, vars);
+            bound.pop();
+        },
+        ExprKind::Let { name, value, body } => {
+            free_vars(value, bound, vars);
+            bound.push(name.clone());
+            free_vars(body, bound, vars);
+            bound.pop();
+        },
+    }
+}
+
+/// The result of checking a program with [`IncrementalCtxt::check`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checked {
+    /// The type scheme of every binding, or the error its binding group
+    /// failed with. Bindings that depend on a failed binding see it with the
+    /// error type, and are not failed because of it. A name bound more than
+    /// once has the scheme of its last binding, the earlier ones are not
+    /// inferred at all.
+    pub schemes: BTreeMap<Ident, TyResult<TyScheme>>,
+
+    /// The bindings that had to be inferred again, the others were cached.
+    pub reinferred: BTreeSet<Ident>,
+}
+
+/// A binding group inferred by a previous check.
+#[derive(Debug, Clone)]
+struct CachedGroup {
+    /// The members of the group, with ids reassigned so that moving a binding
+    /// around in the source doesn't invalidate it.
+    sources: Vec<Decl>,
+
+    /// The schemes of the bindings outside the group that it refers to.
+    deps: BTreeMap<Ident, TyScheme>,
+
+    schemes: TyResult<Vec<TyScheme>>,
+}
+
+/// Type checks programs incrementally, for editors that check the same
+/// program again after every change.
+///
+/// The generalized type scheme of every binding group is cached, and a group
+/// is only inferred again if its source changed or one of the schemes of the
+/// bindings it depends on did.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}};
+/// # use type_infer_rs::program::{Decl, Program, IncrementalCtxt};
+/// let mut program = Program::new(vec![
+///     // let id = λ x. x
+///     Decl::new(
+///         Ident::new("id".to_string()),
+///         Expr::mk_abs(
+///             Ident::new("x".to_string()),
+///             Expr::mk_var(Ident::new("x".to_string())),
+///         ),
+///     ),
+///     // let n = id 1
+///     Decl::new(
+///         Ident::new("n".to_string()),
+///         Expr::mk_app(
+///             Expr::mk_var(Ident::new("id".to_string())),
+///             Expr::mk_lit(Lit::Int(1)),
+///         ),
+///     ),
+/// ]);
+///
+/// let mut incr = IncrementalCtxt::new();
+/// assert_eq!(incr.check(&program).reinferred.len(), 2);
+///
+/// // Only `n` changes, `id` is not inferred again.
+/// program.decls[1].expr = Expr::mk_app(
+///     Expr::mk_var(Ident::new("id".to_string())),
+///     Expr::mk_lit(Lit::Bool(true)),
+/// );
+/// let checked = incr.check(&program);
+/// assert_eq!(checked.reinferred.into_iter().collect::<Vec<_>>(),
+///     vec![Ident::new("n".to_string())]);
+/// assert_eq!(
+///     checked.schemes[&Ident::new("n".to_string())].as_ref().unwrap().ty.to_string(),
+///     "bool",
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalCtxt {
+    /// Cached binding groups, keyed by the names of their members.
+    cache: BTreeMap<Vec<Ident>, CachedGroup>,
+
+    /// The names in scope in the whole program, which its bindings shadow.
+    globals: TyCtxt,
+}
+
+impl IncrementalCtxt {
+    pub fn new() -> IncrementalCtxt {
+        IncrementalCtxt::with_globals(TyCtxt::new())
+    }
+
+    /// Checks programs in a context of global names, like the ones of the
+    /// [`crate::prelude`].
+    pub fn with_globals(globals: TyCtxt) -> IncrementalCtxt {
+        IncrementalCtxt { cache: BTreeMap::new(), globals }
+    }
+
+    pub fn check(&mut self, program: &Program) -> Checked {
+        let deps = program.deps();
+        let shadowed = program.shadowed();
+        let mut env = BTreeMap::<Ident, TyScheme>::new();
+        let mut checked = Checked {
+            schemes: BTreeMap::new(),
+            reinferred: BTreeSet::new(),
+        };
+        let mut cache = BTreeMap::new();
+
+        // A shadowed binding is alone in its group, nothing refers to it.
+        for group in program.groups().into_iter().filter(|group| !shadowed[group[0]]) {
+            let key = group.iter()
+                .map(|&i| program.decls[i].name.clone())
+                .collect::<Vec<_>>();
+            let sources = group.iter()
+                .map(|&i| {
+                    let decl = &program.decls[i];
+                    Decl::new(decl.name.clone(), decl.expr.clone().assign_ids())
+                })
+                .collect::<Vec<_>>();
+            let group_deps = group.iter()
+                .flat_map(|&i| deps[i].iter())
+                .filter(|j| !group.contains(j))
+                .map(|&j| &program.decls[j].name)
+                .map(|name| (name.clone(), env[name].clone()))
+                .collect::<BTreeMap<_, _>>();
+
+            let cached = self.cache.remove(&key)
+                .filter(|c| c.sources == sources && c.deps == group_deps);
+            let cached = match cached {
+                Some(cached) => cached,
+                None => {
+                    checked.reinferred.extend(key.iter().cloned());
+                    let schemes = infer_group(&mut InferCtxt::new(), &self.globals, &group_deps, &sources);
+                    CachedGroup { sources, deps: group_deps, schemes }
+                },
+            };
+
+            for (i, name) in key.iter().enumerate() {
+                let tys = cached.schemes.as_ref()
+                    .map(|schemes| schemes[i].clone())
+                    .map_err(Clone::clone);
+                env.insert(name.clone(), match &tys {
+                    Ok(tys) => tys.clone(),
+                    Err(_) => TyScheme::mk_forall(vec![], Ty::mk_error()),
+                });
+                checked.schemes.insert(name.clone(), tys);
+            }
+            cache.insert(key, cached);
+        }
+
+        // Groups that are no longer in the program are dropped.
+        self.cache = cache;
+        checked
+    }
+}
+
+fn infer_group(
+    icx: &mut InferCtxt,
+    globals: &TyCtxt,
+    deps: &BTreeMap<Ident, TyScheme>,
+    sources: &[Decl],
+) -> TyResult<Vec<TyScheme>> {
+    let mut tcx = globals.clone();
+    for (name, tys) in deps {
+        tcx.insert(name.clone(), tys.clone());
+    }
+    let bindings = sources.iter()
+        .map(|decl| (decl.name.clone(), &decl.expr))
+        .collect::<Vec<_>>();
+    let schemes = icx.infer_bindings(&tcx, &bindings)?;
+    Ok(schemes.iter().map(TyScheme::normalize).collect())
+}
+
+/// The scheduling state shared by the workers of [`check_parallel`].
+struct Schedule {
+    /// The number of groups each group waits for.
+    waiting_for: Vec<usize>,
+    ready: VecDeque<usize>,
+    results: Vec<Option<TyResult<Vec<TyScheme>>>>,
+    unfinished: usize,
+}
+
+/// Type checks a program on a pool of threads, in a context of global names
+/// like [`IncrementalCtxt::with_globals`].
+///
+/// Binding groups are inferred as soon as all the groups they depend on are,
+/// independent groups in parallel. Like in [`IncrementalCtxt::check`], every
+/// group is inferred with a fresh [`InferCtxt`], so the type variables in the
+/// results, errors included, don't depend on which worker inferred what.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, ty::ty_ctxt::TyCtxt};
+/// # use type_infer_rs::program::{Decl, Program, IncrementalCtxt, check_parallel};
+/// let program = Program::new((0..16)
+///     .map(|i| Decl::new(
+///         Ident::new(format!("k{}", i)),
+///         // let kI = λ x. λ y. x
+///         Expr::mk_abs(
+///             Ident::new("x".to_string()),
+///             Expr::mk_abs(
+///                 Ident::new("y".to_string()),
+///                 Expr::mk_var(Ident::new("x".to_string())),
+///             ),
+///         ),
+///     ))
+///     .collect());
+///
+/// let schemes = check_parallel(&program, &TyCtxt::new(), 4);
+/// assert_eq!(schemes, IncrementalCtxt::new().check(&program).schemes);
+/// assert_eq!(
+///     schemes[&Ident::new("k7".to_string())].as_ref().unwrap().to_string(),
+///     "forall a b . a -> b -> a",
+/// );
+/// ```
+pub fn check_parallel(
+    program: &Program,
+    globals: &TyCtxt,
+    threads: usize,
+) -> BTreeMap<Ident, TyResult<TyScheme>> {
+    let decl_deps = program.deps();
+    let groups = program.groups();
+    let mut group_of = vec![0; program.decls.len()];
+    for (g, group) in groups.iter().enumerate() {
+        for &i in group {
+            group_of[i] = g;
+        }
+    }
+
+    // The groups each group depends on, and the other way around.
+    let mut dependents = vec![BTreeSet::new(); groups.len()];
+    let mut waiting_for = vec![0; groups.len()];
+    for (g, group) in groups.iter().enumerate() {
+        let deps = group.iter()
+            .flat_map(|&i| decl_deps[i].iter().map(|&j| group_of[j]))
+            .filter(|&h| h != g)
+            .collect::<BTreeSet<_>>();
+        waiting_for[g] = deps.len();
+        for h in deps {
+            dependents[h].insert(g);
+        }
+    }
+
+    let schedule = Mutex::new(Schedule {
+        ready: (0..groups.len()).filter(|&g| waiting_for[g] == 0).collect(),
+        waiting_for,
+        results: vec![None; groups.len()],
+        unfinished: groups.len(),
+    });
+    let changed = Condvar::new();
+
+    let worker = || {
+        loop {
+            let mut state = schedule.lock().unwrap();
+            let g = loop {
+                if state.unfinished == 0 {
+                    return;
+                }
+                match state.ready.pop_front() {
+                    Some(g) => break g,
+                    None => state = changed.wait(state).unwrap(),
+                }
+            };
+
+            let sources = groups[g].iter()
+                .map(|&i| program.decls[i].clone())
+                .collect::<Vec<_>>();
+            let deps = groups[g].iter()
+                .flat_map(|&i| decl_deps[i].iter())
+                .filter(|&&j| group_of[j] != g)
+                .map(|&j| {
+                    let h = group_of[j];
+                    let k = groups[h].iter().position(|&m| m == j).unwrap();
+                    let tys = match &state.results[h] {
+                        Some(Ok(schemes)) => schemes[k].clone(),
+                        _ => TyScheme::mk_forall(vec![], Ty::mk_error()),
+                    };
+                    (program.decls[j].name.clone(), tys)
+                })
+                .collect();
+            drop(state);
+
+            let result = infer_group(&mut InferCtxt::new(), globals, &deps, &sources);
+
+            let mut state = schedule.lock().unwrap();
+            state.results[g] = Some(result);
+            state.unfinished -= 1;
+            for &h in &dependents[g] {
+                state.waiting_for[h] -= 1;
+                if state.waiting_for[h] == 0 {
+                    state.ready.push_back(h);
+                }
+            }
+            changed.notify_all();
+        }
+    };
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(worker);
+        }
+    });
+
+    // Merge in the order of the groups, not the order they finished in. Like
+    // in `IncrementalCtxt::check`, a name has the scheme of its last binding.
+    let results = schedule.into_inner().unwrap().results;
+    let shadowed = program.shadowed();
+    let mut schemes = BTreeMap::new();
+    for (group, result) in groups.iter().zip(results) {
+        let result = result.expect("every group is inferred");
+        for (k, &i) in group.iter().enumerate().filter(|&(_, &i)| !shadowed[i]) {
+            let tys = result.as_ref().map(|s| s[k].clone()).map_err(Clone::clone);
+            schemes.insert(program.decls[i].name.clone(), tys);
+        }
+    }
+    schemes
+}
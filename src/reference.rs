@@ -0,0 +1,122 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! A slow reference type checker, to validate [`crate::infer::InferCtxt`]
+//! against. It has no type schemes at all: `let x = e0 in e1` is checked by
+//! substituting `e0` for `x` in `e1`, so every use of `x` gets its own copy of
+//! `e0` with its own monomorphic type.
+//!
+//! ```text
+//! Γ ⊢ e0 : τ0     Γ ⊢ e1[x := e0] : τ
+//! ----------------------------------- (Let-Inline)
+//!       Γ ⊢ let x = e0 in e1 : τ
+//! ```
+//!
+//! `e0` is checked on its own as well, so that a binding is still checked
+//! when it is not used. The size of the substituted term is exponential in
+//! the nesting of `let`s, so this is only fit for small programs.
+//!
+//! Numeric type variables are not generalized, so the copies of an integer
+//! literal share the type of the literal they are copied from, which the
+//! uses of all copies decide. The ones left unknown default to `int` at the
+//! end.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{TyError, TyResult},
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit},
+    ty::{Ty, TyVar, subst::Subst, types::Types},
+};
+
+/// Infers the type of a closed expression with let-expansion, stops at the
+/// first type error.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{reference::infer, syntax::parser::parse};
+/// // Each use of `id` is a copy of `λ x. x` with its own type.
+/// let expr = parse("let id = λ x. x in id id 1").unwrap().expr;
+/// assert_eq!(infer(&expr).unwrap().to_string(), "int");
+///
+/// // The unused binding is still checked.
+/// assert!(infer(&parse("let bad = 1 true in 2").unwrap().expr).is_err());
+/// ```
+pub fn infer(expr: &Expr) -> TyResult<Ty> {
+    let mut checker = Checker { used_type_var_id: 0, nums: BTreeMap::new() };
+    let (s, t) = checker.infer(&BTreeMap::new(), expr)?;
+    Ok(t.apply(&s).default_nums())
+}
+
+struct Checker {
+    used_type_var_id: u32,
+
+    /// The type of every integer literal, keyed by its id, which its copies
+    /// share. Every unifier is applied to them at once, so a copy checked
+    /// later sees what the earlier ones decided.
+    nums: BTreeMap<ExprId, Ty>,
+}
+
+impl Checker {
+    fn new_type_var(&mut self) -> Ty {
+        let id = self.used_type_var_id;
+        self.used_type_var_id += 1;
+        Ty::mk_var(TyVar::Unknown(id))
+    }
+
+    fn num_var(&mut self, id: ExprId) -> Ty {
+        if let Some(ty) = self.nums.get(&id) {
+            return ty.clone();
+        }
+        let ty = Ty::mk_var(TyVar::Num(self.used_type_var_id));
+        self.used_type_var_id += 1;
+        self.nums.insert(id, ty.clone());
+        ty
+    }
+
+    /// Infers a type in a context of monotypes.
+    fn infer(&mut self, env: &BTreeMap<Ident, Ty>, expr: &Expr) -> TyResult<(Subst, Ty)> {
+        match &expr.kind {
+            ExprKind::Var(name) => match env.get(name) {
+                Some(ty) => Ok((Subst::identity(), ty.clone())),
+                None => Err(TyError::mk_unbound_var(name.clone())),
+            },
+            ExprKind::Lit(Lit::Int(_)) => Ok((Subst::identity(), self.num_var(expr.id))),
+            ExprKind::Lit(lit) => Ok((Subst::identity(), lit.ty())),
+            ExprKind::Hole(_) => Ok((Subst::identity(), self.new_type_var())),
+            ExprKind::Abs { param, body } => {
+                let param_ty = self.new_type_var();
+                let mut env = env.clone();
+                env.insert(param.clone(), param_ty.clone());
+                let (s, ret_ty) = self.infer(&env, body)?;
+                let ty = Ty::mk_arrow(param_ty.apply(&s), ret_ty);
+                Ok((s, ty))
+            },
+            ExprKind::App { callee, arg } => {
+                let (s1, callee_ty) = self.infer(env, callee)?;
+                let (s2, arg_ty) = self.infer(&apply_env(env, &s1), arg)?;
+                let ret_ty = self.new_type_var();
+                let s3 = Subst::mgu(callee_ty.apply(&s2), Ty::mk_arrow(arg_ty, ret_ty.clone()))?;
+                for ty in self.nums.values_mut() {
+                    *ty = ty.apply(&s3);
+                }
+                let ty = ret_ty.apply(&s3);
+                Ok((s3.compose(&s2).compose(&s1), ty))
+            },
+            ExprKind::Let { name, value, body } => {
+                // The constraints of the value on the context are kept, even
+                // if the name is not used.
+                let (s1, _) = self.infer(env, value)?;
+                let (s2, ty) = self.infer(&apply_env(env, &s1), &body.subst(name, value))?;
+                Ok((s2.compose(&s1), ty))
+            },
+        }
+    }
+}
+
+fn apply_env(env: &BTreeMap<Ident, Ty>, subst: &Subst) -> BTreeMap<Ident, Ty> {
+    env.iter()
+        .map(|(name, ty)| (name.clone(), ty.apply(subst)))
+        .collect()
+}
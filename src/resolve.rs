@@ -0,0 +1,195 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Name resolution, which links every variable to the node that binds it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId},
+    visit::{self, Visit, fold::{self, Fold}},
+};
+
+/// The result of [`resolve`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resolution {
+    /// The `Abs` or `Let` node binding every bound variable, keyed by the id
+    /// of the variable.
+    pub bindings: BTreeMap<ExprId, ExprId>,
+
+    /// The variables that are not bound, in the order they appear.
+    pub unbound: Vec<ExprId>,
+
+    /// The binders that shadow another binder of the same name in scope,
+    /// paired with the shadowed one, in the order they appear.
+    pub shadowed: Vec<(ExprId, ExprId)>,
+}
+
+/// Resolves the variables of an expression. The expression must have its ids
+/// assigned.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{resolve::resolve, syntax::parser::parse};
+/// let expr = parse("λ x. let x = x in y").unwrap().expr;
+/// let resolution = resolve(&expr);
+///
+/// // The `let` shadows the `λ`, and `y` is unbound.
+/// assert_eq!(resolution.shadowed.len(), 1);
+/// assert_eq!(resolution.unbound.len(), 1);
+/// assert_eq!(resolution.bindings.len(), 1);
+/// ```
+pub fn resolve(expr: &Expr) -> Resolution {
+    let mut resolver = Resolver { scope: Vec::new(), resolution: Resolution::default() };
+    resolver.visit_expr(expr);
+    resolver.resolution
+}
+
+struct Resolver {
+    scope: Vec<(Ident, ExprId)>,
+    resolution: Resolution,
+}
+
+impl Resolver {
+    fn bind(&mut self, name: &Ident, binder: ExprId) {
+        if let Some((_, shadowed)) = self.scope.iter().rev().find(|(bound, _)| bound == name) {
+            self.resolution.shadowed.push((binder, *shadowed));
+        }
+        self.scope.push((name.clone(), binder));
+    }
+}
+
+impl Visit for Resolver {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Var(name) => {
+                match self.scope.iter().rev().find(|(bound, _)| bound == name) {
+                    Some((_, binder)) => {
+                        self.resolution.bindings.insert(expr.id, *binder);
+                    },
+                    None => self.resolution.unbound.push(expr.id),
+                }
+            },
+            ExprKind::Abs { param, body } => {
+                self.bind(param, expr.id);
+                self.visit_expr(body);
+                self.scope.pop();
+            },
+            ExprKind::Let { name, value, body } => {
+                self.visit_expr(value);
+                self.bind(name, expr.id);
+                self.visit_expr(body);
+                self.scope.pop();
+            },
+            _ => visit::walk_expr(self, expr),
+        }
+    }
+}
+
+/// Renames the binders of an expression so that no two binders have the same
+/// name, and no binder has the name of an unbound variable. The first binder
+/// of a name keeps it, the others get the name with a number appended. The
+/// ids are kept.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{resolve::rename_unique, syntax::parser::parse};
+/// let expr = parse("λ x. λ x1. let x = x x1 in λ x. x").unwrap().expr;
+/// assert_eq!(
+///     rename_unique(&expr).to_string(),
+///     "λ x. λ x1. let x2 = (x x1) in λ x3. x3",
+/// );
+/// ```
+pub fn rename_unique(expr: &Expr) -> Expr {
+    /// All names in the expression, and the names of the variables by id.
+    #[derive(Default)]
+    struct Names {
+        all: BTreeSet<Ident>,
+        vars: BTreeMap<ExprId, Ident>,
+    }
+
+    impl Visit for Names {
+        fn visit_expr(&mut self, expr: &Expr) {
+            match &expr.kind {
+                ExprKind::Var(name) => {
+                    self.all.insert(name.clone());
+                    self.vars.insert(expr.id, name.clone());
+                },
+                ExprKind::Abs { param: name, .. } | ExprKind::Let { name, .. } => {
+                    self.all.insert(name.clone());
+                },
+                _ => {},
+            }
+            visit::walk_expr(self, expr);
+        }
+    }
+
+    let mut names = Names::default();
+    names.visit_expr(expr);
+    let resolution = resolve(expr);
+    let kept = resolution.unbound.iter()
+        .map(|id| names.vars[id].clone())
+        .collect();
+    let mut renamer = Renamer {
+        resolution,
+        taken: names.all,
+        kept,
+        names: BTreeMap::new(),
+    };
+    renamer.fold_expr(expr.clone())
+}
+
+struct Renamer {
+    resolution: Resolution,
+
+    /// The names which a renamed binder must not get.
+    taken: BTreeSet<Ident>,
+
+    /// The names of the unbound variables, and the names kept by a binder so
+    /// far. Other binders of these names are renamed.
+    kept: BTreeSet<Ident>,
+
+    /// The new name of every binder, keyed by its id.
+    names: BTreeMap<ExprId, Ident>,
+}
+
+impl Renamer {
+    fn rename(&mut self, binder: ExprId, name: Ident) -> Ident {
+        let new_name = if self.kept.insert(name.clone()) {
+            name
+        } else {
+            let fresh = (1..)
+                .map(|i| Ident::new(format!("{}{}", name.name, i)))
+                .find(|fresh| !self.taken.contains(fresh))
+                .expect("there are infinitely many names");
+            self.taken.insert(fresh.clone());
+            fresh
+        };
+        self.names.insert(binder, new_name.clone());
+        new_name
+    }
+}
+
+impl Fold for Renamer {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        let kind = match expr.kind {
+            ExprKind::Var(name) => match self.resolution.bindings.get(&expr.id) {
+                Some(binder) => ExprKind::Var(self.names[binder].clone()),
+                None => ExprKind::Var(name),
+            },
+            ExprKind::Abs { param, body } => {
+                let param = self.rename(expr.id, param);
+                ExprKind::Abs { param, body: Box::new(self.fold_expr(*body)) }
+            },
+            ExprKind::Let { name, value, body } => {
+                let value = Box::new(self.fold_expr(*value));
+                let name = self.rename(expr.id, name);
+                ExprKind::Let { name, value, body: Box::new(self.fold_expr(*body)) }
+            },
+            kind => return fold::walk_expr(self, Expr { kind, id: expr.id }),
+        };
+        Expr { kind, id: expr.id }
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Type error slicing.
+//!
+//! Algorithm W reports a type error at the node where unification happens to
+//! fail, which depends on the order the program is traversed in. A type error
+//! slice is instead the set of all nodes that contribute to a conflict: each
+//! node generates a few type equality constraints, and we look for a minimal
+//! set of nodes whose constraints are jointly unsatisfiable.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit},
+    ty::{Ty, TyVar, types::Types, subst::Subst},
+};
+
+/// An equality constraint `lhs = rhs`, labeled with the node it comes from.
+#[derive(Debug, Clone)]
+struct Constraint {
+    id: ExprId,
+    lhs: Ty,
+    rhs: Ty,
+}
+
+/// What a variable in scope is bound to. Let-bound variables remember their
+/// definition, their constraints are generated again at every use, which is
+/// the let-expansion characterization of let-polymorphism.
+#[derive(Clone)]
+enum Binding<'a> {
+    Mono(Ty),
+    Poly(&'a Expr, Env<'a>),
+}
+
+type Env<'a> = BTreeMap<Ident, Binding<'a>>;
+
+struct ConstraintGen {
+    used_type_var_id: u32,
+    constraints: Vec<Constraint>,
+
+    /// The numeric type variable of every integer literal. Like in inference,
+    /// it is not generalized, so it is shared by all the copies of a literal
+    /// that let-expansion makes.
+    nums: BTreeMap<ExprId, Ty>,
+}
+
+impl ConstraintGen {
+    fn new_type_var(&mut self) -> Ty {
+        let id = self.used_type_var_id;
+        self.used_type_var_id += 1;
+        Ty::mk_var(TyVar::Unknown(id))
+    }
+
+    fn num_var(&mut self, id: ExprId) -> Ty {
+        if let Some(ty) = self.nums.get(&id) {
+            return ty.clone();
+        }
+        let var = Ty::mk_var(TyVar::num(self.used_type_var_id));
+        self.used_type_var_id += 1;
+        self.nums.insert(id, var.clone());
+        var
+    }
+
+    fn constrain(&mut self, id: ExprId, lhs: Ty, rhs: Ty) {
+        self.constraints.push(Constraint { id, lhs, rhs });
+    }
+
+    /// Generates the constraints of an expression and returns its type.
+    fn generate<'a>(&mut self, env: &Env<'a>, expr: &'a Expr) -> Ty {
+        let ty = self.new_type_var();
+        match &expr.kind {
+            ExprKind::Var(name) => match env.get(name) {
+                Some(Binding::Mono(var_ty)) =>
+                    self.constrain(expr.id, ty.clone(), var_ty.clone()),
+                Some(Binding::Poly(value, value_env)) => {
+                    let value_ty = self.generate(value_env, value);
+                    self.constrain(expr.id, ty.clone(), value_ty);
+                },
+                // Unbound variables are not a type conflict, they are left
+                // unconstrained here.
+                None => {},
+            },
+            ExprKind::Lit(Lit::Int(_)) => {
+                let num_ty = self.num_var(expr.id);
+                self.constrain(expr.id, ty.clone(), num_ty);
+            },
+            ExprKind::Lit(lit) =>
+                self.constrain(expr.id, ty.clone(), lit.ty()),
+            ExprKind::Abs { param, body } => {
+                let param_ty = self.new_type_var();
+                let mut new_env = env.clone();
+                new_env.insert(param.clone(), Binding::Mono(param_ty.clone()));
+                let body_ty = self.generate(&new_env, body);
+                self.constrain(expr.id, ty.clone(), Ty::mk_arrow(param_ty, body_ty));
+            },
+            ExprKind::App { callee, arg } => {
+                let callee_ty = self.generate(env, callee);
+                let arg_ty = self.generate(env, arg);
+                self.constrain(expr.id, callee_ty, Ty::mk_arrow(arg_ty, ty.clone()));
+            },
+            ExprKind::Let { name, value, body } => {
+                // The bound value must type check even if it is never used.
+                self.generate(env, value);
+                let mut new_env = env.clone();
+                new_env.insert(name.clone(), Binding::Poly(value, env.clone()));
+                let body_ty = self.generate(&new_env, body);
+                self.constrain(expr.id, ty.clone(), body_ty);
+            },
+            // A hole fits any type.
+            ExprKind::Hole(_) => {},
+        }
+        ty
+    }
+}
+
+/// Checks if the constraints of the nodes in `ids` are satisfiable together.
+fn satisfiable(constraints: &[Constraint], ids: &BTreeSet<ExprId>) -> bool {
+    let mut subst = Subst::identity();
+    for c in constraints.iter().filter(|c| ids.contains(&c.id)) {
+        match Subst::mgu(c.lhs.apply(&subst), c.rhs.apply(&subst)) {
+            Ok(s) => subst = s.compose(&subst),
+            Err(_) => return false,
+        }
+    }
+    true
+}
+
+/// Finds a type error slice of an expression, returns `None` if the
+/// expression is well-typed (unbound variables aside).
+///
+/// The slice is minimal: the constraints of all the returned nodes cannot be
+/// satisfied together, but they can if any one of the nodes is left out. Each
+/// use of a let-bound variable re-generates the constraints of its definition,
+/// so this can take exponential time on deeply nested `let`s, it is meant to
+/// run after ordinary inference has already failed.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, expr_id::ExprId, lit::Lit}};
+/// # use type_infer_rs::slice::error_slice;
+/// // (λ x. x 1) true
+/// let expr = Expr::mk_app(
+///     Expr::mk_abs(
+///         Ident::new("x".to_string()),
+///         Expr::mk_app(
+///             Expr::mk_var(Ident::new("x".to_string())),
+///             Expr::mk_lit(Lit::Int(1)),
+///         ),
+///     ),
+///     Expr::mk_lit(Lit::Bool(true)),
+/// ).assign_ids();
+///
+/// // Every node but the literal `1` contributes to the conflict between
+/// // `x : bool` and `x` being applied.
+/// let slice = error_slice(&expr).unwrap();
+/// assert_eq!(slice, [1, 2, 3, 4, 6].into_iter().map(ExprId::from_u32).collect());
+/// ```
+pub fn error_slice(expr: &Expr) -> Option<BTreeSet<ExprId>> {
+    let mut gen = ConstraintGen {
+        used_type_var_id: 0,
+        constraints: Vec::new(),
+        nums: BTreeMap::new(),
+    };
+    gen.generate(&Env::new(), expr);
+    let constraints = gen.constraints;
+
+    let mut slice = constraints.iter().map(|c| c.id).collect::<BTreeSet<_>>();
+    if satisfiable(&constraints, &slice) {
+        return None;
+    }
+
+    // Deletion filter: drop every node whose constraints are not needed for
+    // the conflict, what remains is minimal.
+    for id in slice.clone() {
+        slice.remove(&id);
+        if satisfiable(&constraints, &slice) {
+            slice.insert(id);
+        }
+    }
+    Some(slice)
+}
@@ -0,0 +1,237 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::fmt;
+
+use crate::error::{ParseError, ParseResult};
+
+use super::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(String),
+    Float(String),
+    /// A character literal, with its escape sequence resolved.
+    Char(char),
+    /// A string literal, with its escape sequences resolved.
+    String(String),
+    Hole(Option<String>),
+    True,
+    False,
+    Let,
+    In,
+    Import,
+    Val,
+    Forall,
+    /// `\` or `λ`.
+    Lambda,
+    Dot,
+    Eq,
+    LParen,
+    RParen,
+    /// `;`, which ends a top-level binding.
+    Semi,
+    Colon,
+    /// `->`, in types.
+    Arrow,
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenKind::Ident(name) => write!(f, "identifier `{}`", name),
+            TokenKind::Int(int) => write!(f, "integer `{}`", int),
+            TokenKind::Float(float) => write!(f, "float `{}`", float),
+            TokenKind::Char(char) => write!(f, "character {:?}", char),
+            TokenKind::String(string) => write!(f, "string {:?}", string),
+            TokenKind::Hole(Some(name)) => write!(f, "hole `_{}`", name),
+            TokenKind::Hole(None) => write!(f, "hole `_`"),
+            TokenKind::True => write!(f, "`true`"),
+            TokenKind::False => write!(f, "`false`"),
+            TokenKind::Let => write!(f, "`let`"),
+            TokenKind::In => write!(f, "`in`"),
+            TokenKind::Import => write!(f, "`import`"),
+            TokenKind::Val => write!(f, "`val`"),
+            TokenKind::Forall => write!(f, "`forall`"),
+            TokenKind::Lambda => write!(f, "`λ`"),
+            TokenKind::Dot => write!(f, "`.`"),
+            TokenKind::Eq => write!(f, "`=`"),
+            TokenKind::LParen => write!(f, "`(`"),
+            TokenKind::RParen => write!(f, "`)`"),
+            TokenKind::Semi => write!(f, "`;`"),
+            TokenKind::Colon => write!(f, "`:`"),
+            TokenKind::Arrow => write!(f, "`->`"),
+            TokenKind::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic()
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+/// Returns the offset of the first character at or after `lo` which doesn't
+/// satisfy the predicate.
+fn scan(src: &str, lo: usize, pred: impl Fn(char) -> bool) -> usize {
+    src[lo..].find(|c| !pred(c)).map_or(src.len(), |i| lo + i)
+}
+
+/// Returns the offset after the prefix at `pos`, if the prefix is there and
+/// a digit follows it.
+fn digits_after(src: &str, pos: usize, prefix: &str) -> Option<usize> {
+    src[pos..].strip_prefix(prefix)
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|_| pos + prefix.len())
+}
+
+/// Scans a literal between quotes starting at `lo`, and returns its
+/// characters with escape sequences resolved, and the offset after the
+/// closing quote. The escape sequences are the ones of Rust: `\n`, `\t`,
+/// `\r`, `\0`, `\\`, `\'`, `\"` and `\u{7FFF}`.
+fn scan_quoted(src: &str, lo: usize, quote: char) -> ParseResult<(Vec<char>, usize)> {
+    let mut chars = Vec::new();
+    let mut iter = src[lo + 1..].char_indices().map(|(i, c)| (lo + 1 + i, c));
+    while let Some((i, c)) = iter.next() {
+        let c = match c {
+            _ if c == quote => return Ok((chars, i + 1)),
+            '\\' => match iter.next() {
+                Some((_, 'n')) => '\n',
+                Some((_, 't')) => '\t',
+                Some((_, 'r')) => '\r',
+                Some((_, '0')) => '\0',
+                Some((_, c @ ('\\' | '\'' | '"'))) => c,
+                Some((_, 'u')) => {
+                    let digits = src[i + 2..].strip_prefix('{')
+                        .and_then(|rest| rest.split_once('}'))
+                        .map(|(digits, _)| digits);
+                    let c = digits
+                        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                        .and_then(char::from_u32);
+                    match (digits, c) {
+                        (Some(digits), Some(c)) => {
+                            // Skip the braces and the digits.
+                            iter.nth(digits.len() + 1);
+                            c
+                        },
+                        _ => return Err(ParseError::new(
+                            Span::new(i, i + 2),
+                            "invalid unicode escape, expected `\\u{...}`".to_string(),
+                        )),
+                    }
+                },
+                Some((j, c)) => return Err(ParseError::new(
+                    Span::new(i, j + c.len_utf8()),
+                    format!("unknown escape sequence `\\{}`", c),
+                )),
+                None => break,
+            },
+            c => c,
+        };
+        chars.push(c);
+    }
+    Err(ParseError::new(Span::new(lo, src.len()), format!("unterminated literal, expected `{}`", quote)))
+}
+
+/// Splits the source text into tokens, the last one is always `Eof`. Line
+/// comments start with `--`.
+pub fn tokenize(src: &str) -> ParseResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut lo = 0;
+
+    while let Some(c) = src[lo..].chars().next() {
+        let (kind, hi) = match c {
+            _ if c.is_whitespace() => {
+                lo = scan(src, lo, char::is_whitespace);
+                continue;
+            },
+            '-' if src[lo..].starts_with("--") => {
+                lo = scan(src, lo, |c| c != '\n');
+                continue;
+            },
+            '-' if src[lo..].starts_with("->") => (TokenKind::Arrow, lo + 2),
+            '0'..='9' => {
+                // A `.` or an `e` only continues the literal if a digit
+                // follows, so `λ x. 1.` still ends with a dot.
+                let mut hi = scan(src, lo, |c| c.is_ascii_digit());
+                let mut is_float = false;
+                if let Some(frac) = digits_after(src, hi, ".") {
+                    hi = scan(src, frac, |c| c.is_ascii_digit());
+                    is_float = true;
+                }
+                let exp = ["e", "e+", "e-", "E", "E+", "E-"].iter()
+                    .find_map(|prefix| digits_after(src, hi, prefix));
+                if let Some(exp) = exp {
+                    hi = scan(src, exp, |c| c.is_ascii_digit());
+                    is_float = true;
+                }
+                if is_float {
+                    (TokenKind::Float(src[lo..hi].to_string()), hi)
+                } else {
+                    (TokenKind::Int(src[lo..hi].to_string()), hi)
+                }
+            },
+            '\'' => {
+                let (chars, hi) = scan_quoted(src, lo, '\'')?;
+                match chars[..] {
+                    [char] => (TokenKind::Char(char), hi),
+                    _ => return Err(ParseError::new(
+                        Span::new(lo, hi),
+                        "a character literal must have exactly one character".to_string(),
+                    )),
+                }
+            },
+            '"' => {
+                let (chars, hi) = scan_quoted(src, lo, '"')?;
+                (TokenKind::String(chars.into_iter().collect()), hi)
+            },
+            '_' => {
+                let hi = scan(src, lo + 1, is_ident_continue);
+                let name = &src[lo + 1..hi];
+                let name = if name.is_empty() { None } else { Some(name.to_string()) };
+                (TokenKind::Hole(name), hi)
+            },
+            _ if is_ident_start(c) && c != 'λ' => {
+                let hi = scan(src, lo, is_ident_continue);
+                let kind = match &src[lo..hi] {
+                    "let" => TokenKind::Let,
+                    "in" => TokenKind::In,
+                    "import" => TokenKind::Import,
+                    "val" => TokenKind::Val,
+                    "forall" => TokenKind::Forall,
+                    "true" => TokenKind::True,
+                    "false" => TokenKind::False,
+                    name => TokenKind::Ident(name.to_string()),
+                };
+                (kind, hi)
+            },
+            '\\' | 'λ' => (TokenKind::Lambda, lo + c.len_utf8()),
+            '.' => (TokenKind::Dot, lo + 1),
+            '=' => (TokenKind::Eq, lo + 1),
+            '(' => (TokenKind::LParen, lo + 1),
+            ')' => (TokenKind::RParen, lo + 1),
+            ';' => (TokenKind::Semi, lo + 1),
+            ':' => (TokenKind::Colon, lo + 1),
+            _ => return Err(ParseError::new(
+                Span::new(lo, lo + c.len_utf8()),
+                format!("unexpected character `{}`", c),
+            )),
+        };
+        tokens.push(Token { kind, span: Span::new(lo, hi) });
+        lo = hi;
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, span: Span::new(src.len(), src.len()) });
+    Ok(tokens)
+}
@@ -0,0 +1,7 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+pub mod span;
+pub mod lexer;
+pub mod parser;
+pub mod sexp;
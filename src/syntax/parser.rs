@@ -0,0 +1,382 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    error::{ParseError, ParseResult},
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit},
+    module::Module,
+    program::{Decl, Program},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, types::Types},
+};
+
+use super::{lexer::{tokenize, Token, TokenKind}, span::Span};
+
+/// A parsed expression together with the source locations of its nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parsed {
+    /// The expression, its ids are assigned by the parser and the span
+    /// tables below are keyed by them, so they must not be re-assigned.
+    pub expr: Expr,
+
+    /// The span of every node.
+    pub spans: BTreeMap<ExprId, Span>,
+
+    /// The span of the name bound by every `Abs` and `Let` node.
+    pub binders: BTreeMap<ExprId, Span>,
+}
+
+/// Parses an expression.
+///
+/// ```text
+/// expr ::= "let" ident "=" expr "in" expr
+///        | ("\" | "λ") ident "." expr
+///        | atom+ [lambda | let]
+/// atom ::= ident | int | float | char | string | "true" | "false" | "(" ")"
+///        | "_" | "_" ident | "(" expr ")"
+/// ```
+///
+/// Application is left associative and binds tighter than `λ` and `let`,
+/// whose bodies extend as far to the right as possible.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::syntax::{parser::parse, span::Span};
+/// let parsed = parse("let id = λ x. x in id 42").unwrap();
+/// assert_eq!(parsed.expr.to_string(), "let id = λ x. x in (id 42)");
+/// // Spans are byte offsets, `λ` takes two bytes.
+/// assert_eq!(parsed.spans[&parsed.expr.id], Span::new(0, 25));
+/// assert_eq!(parsed.binders[&parsed.expr.id], Span::new(4, 6));
+/// ```
+pub fn parse(src: &str) -> ParseResult<Parsed> {
+    let mut parser = Parser::new(tokenize(src)?);
+    let expr = parser.parse_expr()?;
+    parser.expect(TokenKind::Eof)?;
+    Ok(Parsed { expr, spans: parser.spans, binders: parser.binders })
+}
+
+/// Parses a program, a sequence of top-level bindings.
+///
+/// ```text
+/// program ::= ("let" ident "=" expr ";")*
+/// ```
+///
+/// The ids of the nodes are unique in the whole program.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::syntax::parser::parse_program;
+/// let program = parse_program("let id = λ x. x; let n = id 1;").unwrap();
+/// assert_eq!(program.decls.len(), 2);
+/// assert_eq!(program.decls[1].expr.to_string(), "(id 1)");
+/// ```
+pub fn parse_program(src: &str) -> ParseResult<Program> {
+    let mut parser = Parser::new(tokenize(src)?);
+    parser.parse_decls()
+}
+
+/// Parses a module, a program that starts with the modules it imports. The
+/// name of the module is not part of its source, it is usually the name of
+/// the file.
+///
+/// ```text
+/// module ::= ("import" ident ";")* program
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::expr::Ident, syntax::parser::parse_module};
+/// let module = parse_module(Ident::new("main".to_string()), "
+///     import list;
+///     let xs = cons 1 nil;
+/// ").unwrap();
+/// assert_eq!(module.imports, [Ident::new("list".to_string())]);
+/// assert_eq!(module.program.decls.len(), 1);
+/// ```
+pub fn parse_module(name: Ident, src: &str) -> ParseResult<Module> {
+    let mut parser = Parser::new(tokenize(src)?);
+    let mut imports = Vec::new();
+    while parser.peek().kind == TokenKind::Import {
+        parser.bump();
+        imports.push(parser.expect_ident()?.0);
+        parser.expect(TokenKind::Semi)?;
+    }
+    let program = parser.parse_decls()?;
+    Ok(Module::new(name, imports, program))
+}
+
+/// Parses a signature, the declared type schemes of the bindings a module
+/// exports.
+///
+/// ```text
+/// signature ::= ("val" ident ":" scheme ";")*
+/// scheme    ::= ["forall" ident+ "."] ty
+/// ty        ::= ty_atom ["->" ty]
+/// ty_atom   ::= "int" | "float" | "bool" | "char" | "string" | "unit"
+///             | ident | "(" ty ")"
+/// ```
+///
+/// Without a `forall`, all type variables of the scheme are bound.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::expr::Ident, syntax::parser::parse_signature};
+/// let sig = parse_signature("
+///     val const : forall a b . a -> b -> a;
+///     val apply : (a -> b) -> a -> b;
+/// ").unwrap();
+/// let scheme = |name: &str| sig.get(&Ident::new(name.to_string())).unwrap().to_string();
+/// assert_eq!(scheme("const"), "forall a b . a -> b -> a");
+/// assert_eq!(scheme("apply"), "forall a b . (a -> b) -> a -> b");
+/// ```
+pub fn parse_signature(src: &str) -> ParseResult<TyCtxt> {
+    let mut parser = Parser::new(tokenize(src)?);
+    let mut sig = TyCtxt::new();
+    while parser.peek().kind != TokenKind::Eof {
+        parser.expect(TokenKind::Val)?;
+        let (name, _) = parser.expect_ident()?;
+        parser.expect(TokenKind::Colon)?;
+        let tys = parser.parse_scheme()?;
+        parser.expect(TokenKind::Semi)?;
+        sig.insert(name, tys);
+    }
+    Ok(sig)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    used_expr_id: u32,
+    spans: BTreeMap<ExprId, Span>,
+    binders: BTreeMap<ExprId, Span>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Parser {
+        Parser {
+            tokens,
+            pos: 0,
+            used_expr_id: 0,
+            spans: BTreeMap::new(),
+            binders: BTreeMap::new(),
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn bump(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> ParseResult<Span> {
+        let token = self.bump();
+        if token.kind == kind {
+            Ok(token.span)
+        } else {
+            Err(ParseError::new(
+                token.span,
+                format!("expected {}, found {}", kind, token.kind),
+            ))
+        }
+    }
+
+    fn expect_ident(&mut self) -> ParseResult<(Ident, Span)> {
+        let token = self.bump();
+        match token.kind {
+            TokenKind::Ident(name) => Ok((Ident::new(name), token.span)),
+            kind => Err(ParseError::new(
+                token.span,
+                format!("expected identifier, found {}", kind),
+            )),
+        }
+    }
+
+    fn mk_expr(&mut self, kind: ExprKind, span: Span) -> Expr {
+        self.used_expr_id += 1;
+        let id = ExprId::from_u32(self.used_expr_id);
+        self.spans.insert(id, span);
+        Expr { kind, id }
+    }
+
+    fn span_of(&self, expr: &Expr) -> Span {
+        self.spans[&expr.id]
+    }
+
+    fn parse_decls(&mut self) -> ParseResult<Program> {
+        let mut decls = Vec::new();
+        while self.peek().kind != TokenKind::Eof {
+            self.expect(TokenKind::Let)?;
+            let (name, _) = self.expect_ident()?;
+            self.expect(TokenKind::Eq)?;
+            let expr = self.parse_expr()?;
+            self.expect(TokenKind::Semi)?;
+            decls.push(Decl::new(name, expr));
+        }
+        Ok(Program::new(decls))
+    }
+
+    fn parse_expr(&mut self) -> ParseResult<Expr> {
+        match self.peek().kind {
+            TokenKind::Let => {
+                let lo = self.bump().span;
+                let (name, name_span) = self.expect_ident()?;
+                self.expect(TokenKind::Eq)?;
+                let value = self.parse_expr()?;
+                self.expect(TokenKind::In)?;
+                let body = self.parse_expr()?;
+                let span = lo.to(self.span_of(&body));
+                let expr = self.mk_expr(ExprKind::Let {
+                    name,
+                    value: Box::new(value),
+                    body: Box::new(body),
+                }, span);
+                self.binders.insert(expr.id, name_span);
+                Ok(expr)
+            },
+            TokenKind::Lambda => {
+                let lo = self.bump().span;
+                let (param, param_span) = self.expect_ident()?;
+                self.expect(TokenKind::Dot)?;
+                let body = self.parse_expr()?;
+                let span = lo.to(self.span_of(&body));
+                let expr = self.mk_expr(ExprKind::Abs {
+                    param,
+                    body: Box::new(body),
+                }, span);
+                self.binders.insert(expr.id, param_span);
+                Ok(expr)
+            },
+            _ => self.parse_app(),
+        }
+    }
+
+    fn parse_app(&mut self) -> ParseResult<Expr> {
+        let mut callee = self.parse_atom()?;
+        loop {
+            let arg = match self.peek().kind {
+                // A trailing `λ` or `let` is the last argument.
+                TokenKind::Lambda | TokenKind::Let => self.parse_expr()?,
+                TokenKind::Ident(_) | TokenKind::Int(_) | TokenKind::Float(_)
+                    | TokenKind::Char(_) | TokenKind::String(_) | TokenKind::Hole(_)
+                    | TokenKind::True | TokenKind::False | TokenKind::LParen =>
+                    self.parse_atom()?,
+                _ => return Ok(callee),
+            };
+            let span = self.span_of(&callee).to(self.span_of(&arg));
+            callee = self.mk_expr(ExprKind::App {
+                callee: Box::new(callee),
+                arg: Box::new(arg),
+            }, span);
+        }
+    }
+
+    fn parse_atom(&mut self) -> ParseResult<Expr> {
+        let token = self.bump();
+        let kind = match token.kind {
+            TokenKind::Ident(name) => ExprKind::Var(Ident::new(name)),
+            TokenKind::Int(int) => match int.parse() {
+                Ok(int) => ExprKind::Lit(Lit::Int(int)),
+                Err(_) => return Err(ParseError::new(
+                    token.span,
+                    format!("integer literal `{}` is out of range", int),
+                )),
+            },
+            TokenKind::Float(float) => match float.parse::<f64>() {
+                Ok(float) if float.is_finite() => ExprKind::Lit(Lit::Float(float)),
+                Ok(_) => return Err(ParseError::new(
+                    token.span,
+                    format!("float literal `{}` is out of range", float),
+                )),
+                Err(_) => return Err(ParseError::new(
+                    token.span,
+                    format!("invalid float literal `{}`", float),
+                )),
+            },
+            TokenKind::Char(char) => ExprKind::Lit(Lit::Char(char)),
+            TokenKind::String(string) => ExprKind::Lit(Lit::String(string)),
+            TokenKind::True => ExprKind::Lit(Lit::Bool(true)),
+            TokenKind::False => ExprKind::Lit(Lit::Bool(false)),
+            TokenKind::Hole(name) => ExprKind::Hole(name.map(Ident::new)),
+            TokenKind::LParen if self.peek().kind == TokenKind::RParen => {
+                let hi = self.bump().span;
+                return Ok(self.mk_expr(ExprKind::Lit(Lit::Unit), token.span.to(hi)));
+            },
+            TokenKind::LParen => {
+                let expr = self.parse_expr()?;
+                self.expect(TokenKind::RParen)?;
+                return Ok(expr);
+            },
+            kind => return Err(ParseError::new(
+                token.span,
+                format!("expected expression, found {}", kind),
+            )),
+        };
+        Ok(self.mk_expr(kind, token.span))
+    }
+
+    fn parse_scheme(&mut self) -> ParseResult<TyScheme> {
+        if self.peek().kind != TokenKind::Forall {
+            let ty = self.parse_ty()?;
+            return Ok(TyScheme::mk_forall(ty.ftv().into_iter().collect(), ty));
+        }
+        self.bump();
+        let mut vars = vec![TyVar::new(self.expect_ident()?.0.name)];
+        while self.peek().kind != TokenKind::Dot {
+            vars.push(TyVar::new(self.expect_ident()?.0.name));
+        }
+        self.bump();
+        let lo = self.peek().span;
+        let ty = self.parse_ty()?;
+        if let Some(var) = ty.ftv().into_iter().find(|var| !vars.contains(var)) {
+            return Err(ParseError::new(
+                lo.to(self.tokens[self.pos - 1].span),
+                format!("type variable `{}` is not bound", var),
+            ));
+        }
+        Ok(TyScheme::mk_forall(vars, ty))
+    }
+
+    fn parse_ty(&mut self) -> ParseResult<Ty> {
+        let param = self.parse_ty_atom()?;
+        if self.peek().kind != TokenKind::Arrow {
+            return Ok(param);
+        }
+        self.bump();
+        Ok(Ty::mk_arrow(param, self.parse_ty()?))
+    }
+
+    fn parse_ty_atom(&mut self) -> ParseResult<Ty> {
+        let token = self.bump();
+        match token.kind {
+            TokenKind::Ident(name) => Ok(match name.as_str() {
+                "int" => Ty::mk_int(),
+                "float" => Ty::mk_float(),
+                "bool" => Ty::mk_bool(),
+                "char" => Ty::mk_char(),
+                "string" => Ty::mk_string(),
+                "unit" => Ty::mk_unit(),
+                _ => Ty::mk_var(TyVar::new(name)),
+            }),
+            TokenKind::LParen => {
+                let ty = self.parse_ty()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(ty)
+            },
+            kind => Err(ParseError::new(
+                token.span,
+                format!("expected type, found {}", kind),
+            )),
+        }
+    }
+}
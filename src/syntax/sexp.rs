@@ -0,0 +1,561 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! A stable S-expression format for expressions, types and substitutions,
+//! for writing test fixtures as text files.
+//!
+//! ```text
+//! expr   ::= node | (@ id node)
+//! node   ::= (var x) | lit | (hole) | (hole name)
+//!          | (app expr expr) | (lam x expr) | (let x expr expr)
+//! lit    ::= (int 42) | (float 1.5) | (bool true) | (char c) | (string s)
+//!          | (unit)
+//! tyvar  ::= (var a) | (unknown 3) | (num 3)
+//! ty     ::= int | float | bool | char | string | unit | error | tyvar
+//!          | (-> ty ty)
+//! scheme ::= (forall (tyvar*) ty)
+//! subst  ::= (subst (tyvar ty)*)
+//! ctxt   ::= (ctxt (x scheme)*)
+//! ```
+//!
+//! The ids of the nodes of an expression are written if they are assigned,
+//! either every node of a read expression has an id, or none has and the
+//! nodes are numbered by [`Expr::assign_ids`].
+//!
+//! Atoms that are empty or contain spaces, parentheses, quotes, backslashes
+//! or `;` are written in double quotes. A `;` starts a comment up to the end
+//! of the line.
+
+use std::fmt;
+
+use super::span::Span;
+use crate::{
+    error::{ParseError, ParseResult},
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId, lit::Lit},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, subst::Subst},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SexpKind {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+/// An S-expression, with its location in the text it was parsed from. Built
+/// ones are located at `0..0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sexp {
+    pub kind: SexpKind,
+    pub span: Span,
+}
+
+impl Sexp {
+    pub fn mk_atom(atom: impl Into<String>) -> Sexp {
+        Sexp { kind: SexpKind::Atom(atom.into()), span: Span::new(0, 0) }
+    }
+
+    pub fn mk_list(items: Vec<Sexp>) -> Sexp {
+        Sexp { kind: SexpKind::List(items), span: Span::new(0, 0) }
+    }
+
+    /// Parses exactly one S-expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::syntax::sexp::Sexp;
+    /// let sexp = Sexp::parse("(lam x ; the identity\n (var x))").unwrap();
+    /// assert_eq!(sexp.to_string(), "(lam x (var x))");
+    ///
+    /// assert_eq!(Sexp::parse("(lam x").unwrap_err().to_string(), "6..6: expected `)`");
+    /// ```
+    pub fn parse(src: &str) -> ParseResult<Sexp> {
+        let mut reader = Reader { src, pos: 0 };
+        let sexp = reader.sexp()?;
+        reader.skip_trivia();
+        if reader.pos < src.len() {
+            return Err(reader.error("expected end of input"));
+        }
+        Ok(sexp)
+    }
+
+    /// Parses any number of S-expressions, as in a fixture file.
+    pub fn parse_all(src: &str) -> ParseResult<Vec<Sexp>> {
+        let mut reader = Reader { src, pos: 0 };
+        let mut sexps = Vec::new();
+        loop {
+            reader.skip_trivia();
+            if reader.pos == src.len() {
+                return Ok(sexps);
+            }
+            sexps.push(reader.sexp()?);
+        }
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.span, message.into())
+    }
+
+    pub fn as_atom(&self) -> ParseResult<&str> {
+        match &self.kind {
+            SexpKind::Atom(atom) => Ok(atom),
+            SexpKind::List(_) => Err(self.error("expected an atom")),
+        }
+    }
+
+    /// The head atom and the rest of a list like `(head ...)`.
+    pub fn as_form(&self) -> ParseResult<(&str, &[Sexp])> {
+        match &self.kind {
+            SexpKind::List(items) if !items.is_empty() =>
+                Ok((items[0].as_atom()?, &items[1..])),
+            _ => Err(self.error("expected a list starting with an atom")),
+        }
+    }
+
+    pub fn as_list(&self) -> ParseResult<&[Sexp]> {
+        match &self.kind {
+            SexpKind::List(items) => Ok(items),
+            SexpKind::Atom(_) => Err(self.error("expected a list")),
+        }
+    }
+}
+
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            SexpKind::Atom(atom) if needs_quotes(atom) => {
+                write!(f, "\"")?;
+                for c in atom.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            },
+            SexpKind::Atom(atom) => write!(f, "{}", atom),
+            SexpKind::List(items) => write!(f, "({})",
+                items.iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';' | '\\')
+}
+
+fn needs_quotes(atom: &str) -> bool {
+    atom.is_empty() || atom.chars().any(is_delimiter)
+}
+
+struct Reader<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(Span::new(self.pos, self.pos), message.to_string())
+    }
+
+    fn skip_trivia(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == ';' {
+                self.pos = self.src[self.pos..]
+                    .find('\n')
+                    .map_or(self.src.len(), |i| self.pos + i);
+            } else if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sexp(&mut self) -> ParseResult<Sexp> {
+        self.skip_trivia();
+        let lo = self.pos;
+        let kind = match self.peek() {
+            None => return Err(self.error("expected an S-expression")),
+            Some(')') => return Err(self.error("unexpected `)`")),
+            Some('\\') => return Err(self.error("unexpected `\\` outside a string")),
+            Some('(') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_trivia();
+                    match self.peek() {
+                        None => return Err(self.error("expected `)`")),
+                        Some(')') => break,
+                        Some(_) => items.push(self.sexp()?),
+                    }
+                }
+                self.pos += 1;
+                SexpKind::List(items)
+            },
+            Some('"') => SexpKind::Atom(self.quoted()?),
+            Some(_) => {
+                let len = self.src[self.pos..]
+                    .find(is_delimiter)
+                    .unwrap_or(self.src.len() - self.pos);
+                self.pos += len;
+                SexpKind::Atom(self.src[lo..self.pos].to_string())
+            },
+        };
+        Ok(Sexp { kind, span: Span::new(lo, self.pos) })
+    }
+
+    fn quoted(&mut self) -> ParseResult<String> {
+        self.pos += 1;
+        let mut atom = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(atom),
+                '\\' => {
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some(c @ ('"' | '\\')) => c,
+                        _ => return Err(self.error("unknown escape")),
+                    };
+                    self.pos += 1;
+                    atom.push(escaped);
+                },
+                c => atom.push(c),
+            }
+        }
+    }
+}
+
+/// Values that can be written as an S-expression.
+pub trait ToSexp {
+    fn to_sexp(&self) -> Sexp;
+}
+
+/// Values that can be read back from an S-expression.
+pub trait FromSexp: Sized {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Self>;
+}
+
+/// Parses a value from its S-expression text.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{syntax::sexp::{read, ToSexp}, ty::ty_scheme::TyScheme};
+/// let src = "(forall ((var a)) (-> (var a) (-> int (var a))))";
+/// let tys = read::<TyScheme>(src).unwrap();
+///
+/// assert_eq!(tys.to_string(), "forall a . a -> int -> a");
+/// assert_eq!(tys.to_sexp().to_string(), src);
+/// ```
+pub fn read<T: FromSexp>(src: &str) -> ParseResult<T> {
+    T::from_sexp(&Sexp::parse(src)?)
+}
+
+/// Checks the number of arguments of a form.
+fn arity<'a>(sexp: &Sexp, args: &'a [Sexp], n: usize) -> ParseResult<&'a [Sexp]> {
+    if args.len() == n {
+        Ok(args)
+    } else {
+        Err(sexp.error(format!("expected {} arguments, found {}", n, args.len())))
+    }
+}
+
+fn number<T: std::str::FromStr>(sexp: &Sexp) -> ParseResult<T> {
+    sexp.as_atom()?.parse().map_err(|_| sexp.error("expected a number"))
+}
+
+impl ToSexp for Ident {
+    fn to_sexp(&self) -> Sexp {
+        Sexp::mk_atom(self.name.clone())
+    }
+}
+
+impl FromSexp for Ident {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Ident> {
+        Ok(Ident::new(sexp.as_atom()?.to_string()))
+    }
+}
+
+impl ToSexp for ExprId {
+    fn to_sexp(&self) -> Sexp {
+        Sexp::mk_atom(self.as_u32().to_string())
+    }
+}
+
+impl FromSexp for ExprId {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<ExprId> {
+        match number(sexp)? {
+            0 => Err(sexp.error("0 is not a valid expression id")),
+            id => Ok(ExprId::from_u32(id)),
+        }
+    }
+}
+
+impl ToSexp for Lit {
+    fn to_sexp(&self) -> Sexp {
+        match self {
+            Lit::Int(int) => Sexp::mk_list(vec![Sexp::mk_atom("int"), Sexp::mk_atom(int.to_string())]),
+            Lit::Float(float) => Sexp::mk_list(vec![Sexp::mk_atom("float"), Sexp::mk_atom(format!("{:?}", float))]),
+            Lit::Bool(bool) => Sexp::mk_list(vec![Sexp::mk_atom("bool"), Sexp::mk_atom(bool.to_string())]),
+            Lit::Char(char) => Sexp::mk_list(vec![Sexp::mk_atom("char"), Sexp::mk_atom(char.to_string())]),
+            Lit::String(string) => Sexp::mk_list(vec![Sexp::mk_atom("string"), Sexp::mk_atom(string.clone())]),
+            Lit::Unit => Sexp::mk_list(vec![Sexp::mk_atom("unit")]),
+        }
+    }
+}
+
+impl FromSexp for Lit {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Lit> {
+        match sexp.as_form()? {
+            ("int", args) => Ok(Lit::Int(number(&arity(sexp, args, 1)?[0])?)),
+            ("float", args) => Ok(Lit::Float(number(&arity(sexp, args, 1)?[0])?)),
+            ("bool", args) => match arity(sexp, args, 1)?[0].as_atom()? {
+                "true" => Ok(Lit::Bool(true)),
+                "false" => Ok(Lit::Bool(false)),
+                _ => Err(args[0].error("expected `true` or `false`")),
+            },
+            ("char", args) => {
+                let mut chars = arity(sexp, args, 1)?[0].as_atom()?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(char), None) => Ok(Lit::Char(char)),
+                    _ => Err(args[0].error("expected a single character")),
+                }
+            },
+            ("string", args) => Ok(Lit::String(arity(sexp, args, 1)?[0].as_atom()?.to_string())),
+            ("unit", args) => {
+                arity(sexp, args, 0)?;
+                Ok(Lit::Unit)
+            },
+            _ => Err(sexp.error("expected a literal")),
+        }
+    }
+}
+
+impl ToSexp for Expr {
+    fn to_sexp(&self) -> Sexp {
+        let form = |head: &str, mut args: Vec<Sexp>| {
+            args.insert(0, Sexp::mk_atom(head));
+            Sexp::mk_list(args)
+        };
+        let node = match &self.kind {
+            ExprKind::Var(name) => form("var", vec![name.to_sexp()]),
+            ExprKind::Lit(lit) => lit.to_sexp(),
+            ExprKind::Hole(name) => form("hole", name.iter().map(Ident::to_sexp).collect()),
+            ExprKind::App { callee, arg } =>
+                form("app", vec![callee.to_sexp(), arg.to_sexp()]),
+            ExprKind::Abs { param, body } =>
+                form("lam", vec![param.to_sexp(), body.to_sexp()]),
+            ExprKind::Let { name, value, body } =>
+                form("let", vec![name.to_sexp(), value.to_sexp(), body.to_sexp()]),
+        };
+        if self.id == ExprId::dummy() {
+            node
+        } else {
+            form("@", vec![self.id.to_sexp(), node])
+        }
+    }
+}
+
+impl FromSexp for Expr {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Expr> {
+        /// Reads an expression, and counts its nodes with and without ids.
+        fn go(sexp: &Sexp, ids: &mut (usize, usize)) -> ParseResult<Expr> {
+            if let ("@", args) = sexp.as_form()? {
+                let args = arity(sexp, args, 2)?;
+                let id = ExprId::from_sexp(&args[0])?;
+                let mut expr = go(&args[1], ids)?;
+                if expr.id != ExprId::dummy() {
+                    return Err(args[1].error("expected an expression without an id"));
+                }
+                expr.id = id;
+                ids.0 += 1;
+                ids.1 -= 1;
+                return Ok(expr);
+            }
+            ids.1 += 1;
+            let expr = match sexp.as_form()? {
+                ("int" | "float" | "bool" | "char" | "string" | "unit", _) => Expr::mk_lit(Lit::from_sexp(sexp)?),
+                ("var", args) => Expr::mk_var(Ident::from_sexp(&arity(sexp, args, 1)?[0])?),
+                ("hole", []) => Expr::mk_hole(None),
+                ("hole", args) =>
+                    Expr::mk_hole(Some(Ident::from_sexp(&arity(sexp, args, 1)?[0])?)),
+                ("app", args) => {
+                    let args = arity(sexp, args, 2)?;
+                    Expr::mk_app(go(&args[0], ids)?, go(&args[1], ids)?)
+                },
+                ("lam", args) => {
+                    let args = arity(sexp, args, 2)?;
+                    Expr::mk_abs(Ident::from_sexp(&args[0])?, go(&args[1], ids)?)
+                },
+                ("let", args) => {
+                    let args = arity(sexp, args, 3)?;
+                    Expr::mk_let(Ident::from_sexp(&args[0])?, go(&args[1], ids)?, go(&args[2], ids)?)
+                },
+                (head, _) => return Err(sexp.error(format!("unknown expression `{}`", head))),
+            };
+            Ok(expr)
+        }
+
+        let mut ids = (0, 0);
+        let expr = go(sexp, &mut ids)?;
+        match ids {
+            (_, 0) => Ok(expr),
+            (0, _) => Ok(expr.assign_ids()),
+            _ => Err(sexp.error("expected an id for either every node or none")),
+        }
+    }
+}
+
+impl ToSexp for TyVar {
+    fn to_sexp(&self) -> Sexp {
+        match self {
+            TyVar::Name(name) =>
+                Sexp::mk_list(vec![Sexp::mk_atom("var"), Sexp::mk_atom(name.clone())]),
+            TyVar::Unknown(id) =>
+                Sexp::mk_list(vec![Sexp::mk_atom("unknown"), Sexp::mk_atom(id.to_string())]),
+            TyVar::Num(id) =>
+                Sexp::mk_list(vec![Sexp::mk_atom("num"), Sexp::mk_atom(id.to_string())]),
+        }
+    }
+}
+
+impl FromSexp for TyVar {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<TyVar> {
+        match sexp.as_form()? {
+            ("var", args) => Ok(TyVar::new(arity(sexp, args, 1)?[0].as_atom()?.to_string())),
+            ("unknown", args) => Ok(TyVar::unknown(number(&arity(sexp, args, 1)?[0])?)),
+            ("num", args) => Ok(TyVar::num(number(&arity(sexp, args, 1)?[0])?)),
+            _ => Err(sexp.error("expected a type variable")),
+        }
+    }
+}
+
+impl ToSexp for Ty {
+    fn to_sexp(&self) -> Sexp {
+        match self {
+            Ty::Var(var) => var.to_sexp(),
+            Ty::Int => Sexp::mk_atom("int"),
+            Ty::Float => Sexp::mk_atom("float"),
+            Ty::Bool => Sexp::mk_atom("bool"),
+            Ty::Char => Sexp::mk_atom("char"),
+            Ty::String => Sexp::mk_atom("string"),
+            Ty::Unit => Sexp::mk_atom("unit"),
+            Ty::Error => Sexp::mk_atom("error"),
+            Ty::Arrow(param_ty, ret_ty) =>
+                Sexp::mk_list(vec![Sexp::mk_atom("->"), param_ty.to_sexp(), ret_ty.to_sexp()]),
+        }
+    }
+}
+
+impl FromSexp for Ty {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Ty> {
+        if let SexpKind::Atom(atom) = &sexp.kind {
+            return match atom.as_str() {
+                "int" => Ok(Ty::mk_int()),
+                "float" => Ok(Ty::mk_float()),
+                "bool" => Ok(Ty::mk_bool()),
+                "char" => Ok(Ty::mk_char()),
+                "string" => Ok(Ty::mk_string()),
+                "unit" => Ok(Ty::mk_unit()),
+                "error" => Ok(Ty::mk_error()),
+                _ => Err(sexp.error(format!("unknown type `{}`", atom))),
+            };
+        }
+        match sexp.as_form()? {
+            ("->", args) => {
+                let args = arity(sexp, args, 2)?;
+                Ok(Ty::mk_arrow(Ty::from_sexp(&args[0])?, Ty::from_sexp(&args[1])?))
+            },
+            _ => Ok(Ty::mk_var(TyVar::from_sexp(sexp)?)),
+        }
+    }
+}
+
+impl ToSexp for TyScheme {
+    fn to_sexp(&self) -> Sexp {
+        Sexp::mk_list(vec![
+            Sexp::mk_atom("forall"),
+            Sexp::mk_list(self.vars.iter().map(TyVar::to_sexp).collect()),
+            self.ty.to_sexp(),
+        ])
+    }
+}
+
+impl FromSexp for TyScheme {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<TyScheme> {
+        match sexp.as_form()? {
+            ("forall", args) => {
+                let args = arity(sexp, args, 2)?;
+                let vars = args[0].as_list()?
+                    .iter()
+                    .map(TyVar::from_sexp)
+                    .collect::<ParseResult<Vec<_>>>()?;
+                Ok(TyScheme::mk_forall(vars, Ty::from_sexp(&args[1])?))
+            },
+            _ => Err(sexp.error("expected a type scheme")),
+        }
+    }
+}
+
+impl ToSexp for Subst {
+    fn to_sexp(&self) -> Sexp {
+        let mut items = vec![Sexp::mk_atom("subst")];
+        items.extend(self.iter().map(|(var, ty)| Sexp::mk_list(vec![var.to_sexp(), ty.to_sexp()])));
+        Sexp::mk_list(items)
+    }
+}
+
+impl FromSexp for Subst {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<Subst> {
+        match sexp.as_form()? {
+            ("subst", args) => {
+                let mut subst = Subst::identity();
+                for arg in args {
+                    let pair = arity(arg, arg.as_list()?, 2)?;
+                    subst.insert(TyVar::from_sexp(&pair[0])?, Ty::from_sexp(&pair[1])?);
+                }
+                Ok(subst)
+            },
+            _ => Err(sexp.error("expected a substitution")),
+        }
+    }
+}
+
+impl ToSexp for TyCtxt {
+    fn to_sexp(&self) -> Sexp {
+        let mut items = vec![Sexp::mk_atom("ctxt")];
+        items.extend(self.iter().map(|(name, tys)| Sexp::mk_list(vec![name.to_sexp(), tys.to_sexp()])));
+        Sexp::mk_list(items)
+    }
+}
+
+impl FromSexp for TyCtxt {
+    fn from_sexp(sexp: &Sexp) -> ParseResult<TyCtxt> {
+        match sexp.as_form()? {
+            ("ctxt", args) => {
+                let mut tcx = TyCtxt::new();
+                for arg in args {
+                    let pair = arity(arg, arg.as_list()?, 2)?;
+                    tcx.insert(Ident::from_sexp(&pair[0])?, TyScheme::from_sexp(&pair[1])?);
+                }
+                Ok(tcx)
+            },
+            _ => Err(sexp.error("expected a type context")),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::fmt;
+
+/// A range of byte offsets `lo..hi` in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+impl Span {
+    pub fn new(lo: usize, hi: usize) -> Span {
+        debug_assert!(lo <= hi, "span {lo}..{hi} is reversed");
+        Span { lo, hi }
+    }
+
+    /// The smallest span that covers both spans.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+
+    /// Checks if the offset is in the span. The end is included as well, so
+    /// the cursor right after an identifier still points at it.
+    pub fn contains(self, offset: usize) -> bool {
+        self.lo <= offset && offset <= self.hi
+    }
+
+    pub fn len(self) -> usize {
+        self.hi - self.lo
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.lo == self.hi
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.lo, self.hi)
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::BTreeMap;
+
+use super::{FTy, Term};
+use crate::{
+    error::{SystemFError, SystemFResult},
+    expr::expr::Ident,
+    ty::TyVar,
+};
+
+/// Type checks a closed System F term. It doesn't share any code with
+/// inference, so it can validate the output of elaboration.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::expr::Ident, ty::TyVar, system_f::{FTy, Term, check::type_of}};
+/// let a = TyVar::new("a".to_string());
+/// let x = Ident::new("x".to_string());
+/// // Λ a. λ x : a. x
+/// let id = Term::mk_ty_abs(a.clone(), Term::mk_abs(x.clone(), FTy::Var(a.clone()), Term::Var(x)));
+/// assert_eq!(type_of(&id).unwrap().to_string(), "forall a . a -> a");
+///
+/// // The type variable `b` is not in scope.
+/// let b = TyVar::new("b".to_string());
+/// assert!(type_of(&Term::mk_ty_app(id, FTy::Var(b))).is_err());
+/// ```
+pub fn type_of(term: &Term) -> SystemFResult<FTy> {
+    check(&BTreeMap::new(), &mut Vec::new(), term)
+}
+
+/// Checks that all type variables of a type are in scope.
+fn well_formed(tvars: &[TyVar], ty: &FTy) -> SystemFResult<()> {
+    match ty.ftv().into_iter().find(|var| !tvars.contains(var)) {
+        Some(var) => Err(SystemFError::UnboundTyVar(var)),
+        None => Ok(()),
+    }
+}
+
+fn expect_eq(expected: &FTy, found: &FTy) -> SystemFResult<()> {
+    if expected.alpha_eq(found) {
+        Ok(())
+    } else {
+        Err(SystemFError::Mismatch(expected.clone(), found.clone()))
+    }
+}
+
+fn check(env: &BTreeMap<Ident, FTy>, tvars: &mut Vec<TyVar>, term: &Term) -> SystemFResult<FTy> {
+    match term {
+        Term::Var(name) => env.get(name)
+            .cloned()
+            .ok_or_else(|| SystemFError::UnboundVar(name.clone())),
+        Term::Lit(lit) => Ok(FTy::from(&lit.ty())),
+        Term::Hole { ty, .. } => {
+            well_formed(tvars, ty)?;
+            Ok(ty.clone())
+        },
+        Term::Abs { param, param_ty, body } => {
+            well_formed(tvars, param_ty)?;
+            let mut env = env.clone();
+            env.insert(param.clone(), param_ty.clone());
+            let ret_ty = check(&env, tvars, body)?;
+            Ok(FTy::mk_arrow(param_ty.clone(), ret_ty))
+        },
+        Term::App { callee, arg } => match check(env, tvars, callee)? {
+            FTy::Arrow(param_ty, ret_ty) => {
+                expect_eq(&param_ty, &check(env, tvars, arg)?)?;
+                Ok(*ret_ty)
+            },
+            ty => Err(SystemFError::NotAFunction(ty)),
+        },
+        Term::TyAbs { var, body } => {
+            tvars.push(var.clone());
+            let body_ty = check(env, tvars, body);
+            tvars.pop();
+            Ok(FTy::mk_forall(var.clone(), body_ty?))
+        },
+        Term::TyApp { term, ty } => {
+            well_formed(tvars, ty)?;
+            match check(env, tvars, term)? {
+                FTy::Forall(var, body) => Ok(body.subst(&var, ty)),
+                ty => Err(SystemFError::NotPolymorphic(ty)),
+            }
+        },
+        Term::Let { name, ty, value, body } => {
+            well_formed(tvars, ty)?;
+            expect_eq(ty, &check(env, tvars, value)?)?;
+            let mut env = env.clone();
+            env.insert(name.clone(), ty.clone());
+            check(&env, tvars, body)
+        },
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use std::collections::BTreeMap;
+
+use super::{FTy, Term};
+use crate::{
+    error::TyResult,
+    expr::{expr::{Expr, ExprKind, Ident}, expr_id::ExprId},
+    infer::{InferCtxt, Recovered},
+    ty::{Ty, TyVar, ty_scheme::TyScheme, subst::Subst, types::Types},
+};
+
+/// Translates a well-typed expression into System F. Every generalization of
+/// a `let` becomes a type abstraction, and every instantiation of a variable
+/// becomes type applications. The type variables left free in the whole
+/// term are abstracted at the top, so the term is closed.
+///
+/// Returns the first type error if the expression doesn't type check.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::{expr::{Expr, Ident}, lit::Lit}, system_f::{elab::elaborate, check::type_of}};
+/// // let id = λ x. x in id 42
+/// let expr = Expr::mk_let(
+///     Ident::new("id".to_string()),
+///     Expr::mk_abs(Ident::new("x".to_string()), Expr::mk_var(Ident::new("x".to_string()))),
+///     Expr::mk_app(Expr::mk_var(Ident::new("id".to_string())), Expr::mk_lit(Lit::Int(42))),
+/// ).assign_ids();
+///
+/// let term = elaborate(&expr).unwrap();
+/// assert_eq!(
+///     term.to_string(),
+///     "let id : forall ?0 . ?0 -> ?0 = Λ ?0. λ x : ?0. x in ((id [int]) 42)",
+/// );
+/// assert_eq!(type_of(&term).unwrap().to_string(), "int");
+/// ```
+pub fn elaborate(expr: &Expr) -> TyResult<Term> {
+    let recovered = InferCtxt::new().infer_recovering(expr);
+    if let Some((_, err)) = recovered.errors.first() {
+        return Err(err.clone());
+    }
+    let term = Elaborator { recovered: &recovered }.elaborate(&BTreeMap::new(), expr);
+
+    let mut free = Vec::new();
+    collect_vars(expr, &recovered, &mut free);
+    Ok(free.into_iter().rev().fold(term, |term, var| Term::mk_ty_abs(var, term)))
+}
+
+/// The type variables free in the types of all nodes, in order of
+/// appearance. The bound variables of `let`s are not free.
+fn collect_vars(expr: &Expr, recovered: &Recovered, vars: &mut Vec<TyVar>) {
+    fn go(expr: &Expr, recovered: &Recovered, bound: &mut Vec<TyVar>, vars: &mut Vec<TyVar>) {
+        for var in recovered.types[&expr.id].ftv() {
+            if !bound.contains(&var) && !vars.contains(&var) {
+                vars.push(var);
+            }
+        }
+        match &expr.kind {
+            ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) => {},
+            ExprKind::App { callee, arg } => {
+                go(callee, recovered, bound, vars);
+                go(arg, recovered, bound, vars);
+            },
+            ExprKind::Abs { body, .. } => go(body, recovered, bound, vars),
+            ExprKind::Let { value, body, .. } => {
+                let generalized = recovered.schemes[&expr.id].vars.len();
+                bound.extend(recovered.schemes[&expr.id].vars.iter().cloned());
+                go(value, recovered, bound, vars);
+                bound.truncate(bound.len() - generalized);
+                go(body, recovered, bound, vars);
+            },
+        }
+    }
+
+    go(expr, recovered, &mut Vec::new(), vars)
+}
+
+struct Elaborator<'a> {
+    recovered: &'a Recovered,
+}
+
+impl Elaborator<'_> {
+    fn ty(&self, id: ExprId) -> &Ty {
+        &self.recovered.types[&id]
+    }
+
+    fn elaborate(&self, env: &BTreeMap<Ident, TyScheme>, expr: &Expr) -> Term {
+        match &expr.kind {
+            ExprKind::Var(name) => {
+                // The instantiation is found by matching the scheme against
+                // the type of the occurrence.
+                let tys = &env[name];
+                let subst = Subst::matching(&tys.ty, self.ty(expr.id))
+                    .expect("an occurrence has an instance of its scheme");
+                tys.vars.iter().fold(Term::Var(name.clone()), |term, var| {
+                    let arg = subst.get(var).unwrap_or_else(|| Ty::mk_var(var.clone()));
+                    Term::mk_ty_app(term, FTy::from(&arg))
+                })
+            },
+            // An integer literal inferred as `float` is a float literal, the
+            // checker gives every literal its base type.
+            ExprKind::Lit(lit) => Term::Lit(lit.at_ty(self.ty(expr.id))),
+            ExprKind::Hole(name) =>
+                Term::Hole { name: name.clone(), ty: FTy::from(self.ty(expr.id)) },
+            ExprKind::App { callee, arg } =>
+                Term::mk_app(self.elaborate(env, callee), self.elaborate(env, arg)),
+            ExprKind::Abs { param, body } => {
+                let Ty::Arrow(param_ty, _) = self.ty(expr.id) else {
+                    unreachable!("an abstraction has a function type");
+                };
+                let mut env = env.clone();
+                env.insert(param.clone(), TyScheme::mk_forall(vec![], (**param_ty).clone()));
+                Term::mk_abs(param.clone(), FTy::from(&**param_ty), self.elaborate(&env, body))
+            },
+            ExprKind::Let { name, value, body } => {
+                let tys = &self.recovered.schemes[&expr.id];
+                let value = tys.vars.iter()
+                    .rev()
+                    .fold(self.elaborate(env, value), |term, var| {
+                        Term::mk_ty_abs(var.clone(), term)
+                    });
+                let mut env = env.clone();
+                env.insert(name.clone(), tys.clone());
+                Term::mk_let(name.clone(), FTy::from(tys), value, self.elaborate(&env, body))
+            },
+        }
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Explicitly typed System F, the target of elaboration. Type abstractions
+//! and applications are explicit, and so are the types of λ parameters.
+
+pub mod elab;
+pub mod check;
+
+use std::{fmt, collections::BTreeSet};
+
+use crate::{expr::{expr::Ident, lit::Lit}, ty::{Ty, TyVar, ty_scheme::TyScheme}};
+
+/// A System F type, where `∀` may appear anywhere.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FTy {
+    Var(TyVar),
+    Int,
+    Float,
+    Bool,
+    Char,
+    String,
+    Unit,
+    Arrow(Box<FTy>, Box<FTy>),
+    Forall(TyVar, Box<FTy>),
+}
+
+impl FTy {
+    pub fn mk_arrow(param: FTy, body: FTy) -> FTy {
+        FTy::Arrow(Box::new(param), Box::new(body))
+    }
+
+    pub fn mk_forall(var: TyVar, body: FTy) -> FTy {
+        FTy::Forall(var, Box::new(body))
+    }
+
+    pub fn ftv(&self) -> BTreeSet<TyVar> {
+        match self {
+            FTy::Var(var) => BTreeSet::from([var.clone()]),
+            FTy::Int | FTy::Float | FTy::Bool | FTy::Char | FTy::String | FTy::Unit =>
+                BTreeSet::new(),
+            FTy::Arrow(param_ty, ret_ty) =>
+                param_ty.ftv().union(&ret_ty.ftv()).cloned().collect(),
+            FTy::Forall(var, body) => {
+                let mut ftv = body.ftv();
+                ftv.remove(var);
+                ftv
+            },
+        }
+    }
+
+    /// Substitutes a type for a free type variable, renaming bound variables
+    /// that would capture the free variables of the type.
+    pub fn subst(&self, var: &TyVar, ty: &FTy) -> FTy {
+        match self {
+            FTy::Var(v) if v == var => ty.clone(),
+            FTy::Var(_) | FTy::Int | FTy::Float | FTy::Bool | FTy::Char | FTy::String
+                | FTy::Unit => self.clone(),
+            FTy::Arrow(param_ty, ret_ty) =>
+                FTy::mk_arrow(param_ty.subst(var, ty), ret_ty.subst(var, ty)),
+            FTy::Forall(bound, _) if bound == var => self.clone(),
+            FTy::Forall(bound, body) if ty.ftv().contains(bound) => {
+                let mut avoid = ty.ftv();
+                avoid.extend(body.ftv());
+                let fresh = fresh_var(bound, &avoid);
+                let body = body.subst(bound, &FTy::Var(fresh.clone()));
+                FTy::mk_forall(fresh, body.subst(var, ty))
+            },
+            FTy::Forall(bound, body) => FTy::mk_forall(bound.clone(), body.subst(var, ty)),
+        }
+    }
+
+    /// Decides whether two types are equal up to the names of bound type
+    /// variables.
+    pub fn alpha_eq(&self, other: &FTy) -> bool {
+        fn go(ty1: &FTy, ty2: &FTy, bound: &mut Vec<(TyVar, TyVar)>) -> bool {
+            match (ty1, ty2) {
+                (FTy::Var(v1), FTy::Var(v2)) => {
+                    // The innermost binder of either variable decides.
+                    let b1 = bound.iter().rposition(|(b, _)| b == v1);
+                    let b2 = bound.iter().rposition(|(_, b)| b == v2);
+                    match (b1, b2) {
+                        (None, None) => v1 == v2,
+                        (b1, b2) => b1 == b2,
+                    }
+                },
+                (FTy::Int, FTy::Int) | (FTy::Float, FTy::Float) | (FTy::Bool, FTy::Bool)
+                    | (FTy::Char, FTy::Char) | (FTy::String, FTy::String)
+                    | (FTy::Unit, FTy::Unit) => true,
+                (FTy::Arrow(p1, r1), FTy::Arrow(p2, r2)) =>
+                    go(p1, p2, bound) && go(r1, r2, bound),
+                (FTy::Forall(v1, body1), FTy::Forall(v2, body2)) => {
+                    bound.push((v1.clone(), v2.clone()));
+                    let eq = go(body1, body2, bound);
+                    bound.pop();
+                    eq
+                },
+                _ => false,
+            }
+        }
+
+        go(self, other, &mut Vec::new())
+    }
+}
+
+/// A name like `var'`, `var''`, ... that is not in the set.
+fn fresh_var(var: &TyVar, avoid: &BTreeSet<TyVar>) -> TyVar {
+    let mut name = var.to_string();
+    loop {
+        name.push('\'');
+        let fresh = TyVar::new(name.clone());
+        if !avoid.contains(&fresh) {
+            return fresh;
+        }
+    }
+}
+
+impl From<&Ty> for FTy {
+    /// Converts a monotype, the error type is not a System F type.
+    fn from(ty: &Ty) -> FTy {
+        match ty {
+            Ty::Var(var) => FTy::Var(var.clone()),
+            Ty::Int => FTy::Int,
+            Ty::Float => FTy::Float,
+            Ty::Bool => FTy::Bool,
+            Ty::Char => FTy::Char,
+            Ty::String => FTy::String,
+            Ty::Unit => FTy::Unit,
+            Ty::Arrow(param_ty, ret_ty) =>
+                FTy::mk_arrow(FTy::from(&**param_ty), FTy::from(&**ret_ty)),
+            Ty::Error => unreachable!("only well-typed expressions are elaborated"),
+        }
+    }
+}
+
+impl From<&TyScheme> for FTy {
+    fn from(tys: &TyScheme) -> FTy {
+        tys.vars.iter()
+            .rev()
+            .fold(FTy::from(&tys.ty), |ty, var| FTy::mk_forall(var.clone(), ty))
+    }
+}
+
+impl fmt::Display for FTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FTy::Var(var) => write!(f, "{}", var),
+            FTy::Int => write!(f, "int"),
+            FTy::Float => write!(f, "float"),
+            FTy::Bool => write!(f, "bool"),
+            FTy::Char => write!(f, "char"),
+            FTy::String => write!(f, "string"),
+            FTy::Unit => write!(f, "unit"),
+            FTy::Arrow(param_ty, ret_ty) => match **param_ty {
+                FTy::Arrow(_, _) | FTy::Forall(_, _) =>
+                    write!(f, "({}) -> {}", param_ty, ret_ty),
+                _ => write!(f, "{} -> {}", param_ty, ret_ty),
+            },
+            FTy::Forall(var, body) => write!(f, "forall {} . {}", var, body),
+        }
+    }
+}
+
+/// A System F term.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Var(Ident),
+    Lit(Lit),
+    Hole { name: Option<Ident>, ty: FTy },
+    Abs { param: Ident, param_ty: FTy, body: Box<Term> },
+    App { callee: Box<Term>, arg: Box<Term> },
+
+    /// A type abstraction `Λ a. e`.
+    TyAbs { var: TyVar, body: Box<Term> },
+
+    /// A type application `e [τ]`.
+    TyApp { term: Box<Term>, ty: FTy },
+    Let { name: Ident, ty: FTy, value: Box<Term>, body: Box<Term> },
+}
+
+impl Term {
+    pub fn mk_abs(param: Ident, param_ty: FTy, body: Term) -> Term {
+        Term::Abs { param, param_ty, body: Box::new(body) }
+    }
+
+    pub fn mk_app(callee: Term, arg: Term) -> Term {
+        Term::App { callee: Box::new(callee), arg: Box::new(arg) }
+    }
+
+    pub fn mk_ty_abs(var: TyVar, body: Term) -> Term {
+        Term::TyAbs { var, body: Box::new(body) }
+    }
+
+    pub fn mk_ty_app(term: Term, ty: FTy) -> Term {
+        Term::TyApp { term: Box::new(term), ty }
+    }
+
+    pub fn mk_let(name: Ident, ty: FTy, value: Term, body: Term) -> Term {
+        Term::Let { name, ty, value: Box::new(value), body: Box::new(body) }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::Var(name) => write!(f, "{}", name),
+            Term::Lit(lit) => write!(f, "{}", lit),
+            Term::Hole { name: Some(name), ty } => write!(f, "(_{} : {})", name, ty),
+            Term::Hole { name: None, ty } => write!(f, "(_ : {})", ty),
+            Term::Abs { param, param_ty, body } =>
+                write!(f, "λ {} : {}. {}", param, param_ty, body),
+            Term::App { callee, arg } => write!(f, "({} {})", callee, arg),
+            Term::TyAbs { var, body } => write!(f, "Λ {}. {}", var, body),
+            Term::TyApp { term, ty } => write!(f, "({} [{}])", term, ty),
+            Term::Let { name, ty, value, body } =>
+                write!(f, "let {} : {} = {} in {}", name, ty, value, body),
+        }
+    }
+}
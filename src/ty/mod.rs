@@ -6,16 +6,27 @@ pub mod ty_scheme;
 pub mod subst;
 pub mod types;
 
-use std::{fmt, collections::BTreeSet};
+use std::{fmt, collections::{BTreeMap, BTreeSet}};
 
 use self::{subst::Subst, types::Types};
+use crate::visit::{Visit, visit_mut::{self, VisitMut}};
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Ty {
     Var(TyVar),
     Int,
+    Float,
     Bool,
+    Char,
+    String,
+    Unit,
     Arrow(Box<Ty>, Box<Ty>),
+
+    /// The type of an expression that failed to type check. It unifies with
+    /// any other type, so errors caused by an earlier error are not reported
+    /// again.
+    Error,
 }
 
 impl Ty {
@@ -27,40 +38,115 @@ impl Ty {
         Ty::Int
     }
 
+    pub fn mk_float() -> Ty {
+        Ty::Float
+    }
+
     pub fn mk_bool() -> Ty {
         Ty::Bool
     }
 
+    pub fn mk_char() -> Ty {
+        Ty::Char
+    }
+
+    pub fn mk_string() -> Ty {
+        Ty::String
+    }
+
+    pub fn mk_unit() -> Ty {
+        Ty::Unit
+    }
+
+    /// Whether the type is a base type, like `int`.
+    pub fn is_base(&self) -> bool {
+        matches!(self, Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String | Ty::Unit)
+    }
+
+    /// Whether a numeric type variable may be unified with the type.
+    pub fn may_be_num(&self) -> bool {
+        matches!(self, Ty::Var(_) | Ty::Int | Ty::Float | Ty::Error)
+    }
+
     pub fn mk_arrow(param: Ty, body: Ty) -> Ty {
         Ty::Arrow(Box::new(param), Box::new(body))
     }
+
+    pub fn mk_error() -> Ty {
+        Ty::Error
+    }
+
+    /// Replaces the numeric type variables left in the type with `int`, the
+    /// type they default to.
+    pub fn default_nums(&self) -> Ty {
+        struct Defaulter;
+
+        impl VisitMut for Defaulter {
+            fn visit_ty_mut(&mut self, ty: &mut Ty) {
+                match ty {
+                    Ty::Var(TyVar::Num(_)) => *ty = Ty::mk_int(),
+                    _ => visit_mut::walk_ty(self, ty),
+                }
+            }
+        }
+
+        let mut ty = self.clone();
+        Defaulter.visit_ty_mut(&mut ty);
+        ty
+    }
+
+    /// Renames type variables in one pass. Applying a substitution would
+    /// also rename the new names again, and loop on swaps like `[a: b, b: a]`.
+    pub(crate) fn rename(&self, names: &BTreeMap<TyVar, TyVar>) -> Ty {
+        struct Renamer<'a>(&'a BTreeMap<TyVar, TyVar>);
+
+        impl VisitMut for Renamer<'_> {
+            fn visit_ty_var_mut(&mut self, var: &mut TyVar) {
+                if let Some(name) = self.0.get(var) {
+                    *var = name.clone();
+                }
+            }
+        }
+
+        let mut ty = self.clone();
+        Renamer(names).visit_ty_mut(&mut ty);
+        ty
+    }
 }
 
 impl Types for Ty {
     fn ftv(&self) -> BTreeSet<TyVar> {
-        match self {
-            Ty::Var(var) => BTreeSet::from([var.clone()]),
-            Ty::Int | Ty::Bool => BTreeSet::new(),
-            Ty::Arrow(param_ty, ret_ty) =>
-                param_ty.ftv().union(&ret_ty.ftv()).cloned().collect(),
+        struct FreeVars(BTreeSet<TyVar>);
+
+        impl Visit for FreeVars {
+            fn visit_ty_var(&mut self, var: &TyVar) {
+                self.0.insert(var.clone());
+            }
         }
+
+        let mut ftv = FreeVars(BTreeSet::new());
+        ftv.visit_ty(self);
+        ftv.0
     }
 
     fn apply(&self, subst: &Subst) -> Ty {
-        match self {
-            Ty::Var(name) => {
-                match subst.get(name) {
-                    Some(found_ty) if &found_ty != self => {
-                        found_ty.apply(subst)
-                    }
-                    _ => self.clone(),
+        struct Applier<'a>(&'a Subst);
+
+        impl VisitMut for Applier<'_> {
+            fn visit_ty_mut(&mut self, ty: &mut Ty) {
+                match ty {
+                    Ty::Var(name) => match self.0.get(name) {
+                        Some(found_ty) if &found_ty != ty => *ty = found_ty.apply(self.0),
+                        _ => {},
+                    },
+                    _ => visit_mut::walk_ty(self, ty),
                 }
             }
-            Ty::Arrow(param_ty, ret_ty) => {
-                Ty::mk_arrow(param_ty.apply(subst), ret_ty.apply(subst))
-            }
-            _ => self.clone(),
         }
+
+        let mut ty = self.clone();
+        Applier(subst).visit_ty_mut(&mut ty);
+        ty
     }
 }
 
@@ -69,7 +155,12 @@ impl fmt::Display for Ty {
         match self {
             Ty::Var(var) => write!(f, "{}", var),
             Ty::Int => write!(f, "int"),
+            Ty::Float => write!(f, "float"),
             Ty::Bool => write!(f, "bool"),
+            Ty::Char => write!(f, "char"),
+            Ty::String => write!(f, "string"),
+            Ty::Unit => write!(f, "unit"),
+            Ty::Error => write!(f, "<error>"),
             Ty::Arrow(param_ty, ret_ty) => match **param_ty {
                 Ty::Arrow(_, _) => write!(f, "({}) -> {}", param_ty, ret_ty),
                 _ => write!(f, "{} -> {}", param_ty, ret_ty),
@@ -78,10 +169,16 @@ impl fmt::Display for Ty {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TyVar {
     Name(String),
     Unknown(u32),
+
+    /// An unknown numeric type, the type of an integer literal. It is only
+    /// unified with `int`, `float` and other type variables, and defaults to
+    /// `int` if nothing else decides it.
+    Num(u32),
 }
 
 impl TyVar {
@@ -92,13 +189,21 @@ impl TyVar {
     pub fn unknown(id: u32) -> TyVar {
         TyVar::Unknown(id)
     }
+
+    pub fn num(id: u32) -> TyVar {
+        TyVar::Num(id)
+    }
+
+    pub fn is_num(&self) -> bool {
+        matches!(self, TyVar::Num(_))
+    }
 }
 
 impl fmt::Display for TyVar {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             TyVar::Name(name) => write!(f, "{}", name),
-            TyVar::Unknown(id) => write!(f, "?{id}"),
+            TyVar::Unknown(id) | TyVar::Num(id) => write!(f, "?{id}"),
         }
     }
 }
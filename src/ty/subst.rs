@@ -7,7 +7,11 @@ use super::{TyVar, Ty};
 use crate::{subst, ty::types::Types, error::{TyResult, TyError}};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Subst {
+    // Type variables are not strings, so the mapping is a list of pairs in
+    // formats whose maps only have string keys.
+    #[cfg_attr(feature = "serde", serde(with = "pairs"))]
     mapping: BTreeMap<TyVar, Ty>,
 }
 
@@ -17,7 +21,7 @@ impl Subst {
     /// # Examples
     ///
     /// ```
-    /// # use type_infer_rs::ty::{Ty, TyVar, subst::Subst};
+    /// # use type_infer_rs::ty::{Ty, TyVar, subst::Subst, types::Types};
     /// let var_a = Ty::Var(TyVar::new("a".to_string()));
     ///
     /// // Identity substitution maps every variable to itself.
@@ -127,13 +131,43 @@ impl Subst {
             Ty::Var(ref v) if v == &var =>
                 Ok(Subst::identity()),
             _ if ty.ftv().contains(&var) =>
-                Err(TyError::Unknown(format!("occur check fails {var} in {ty}"))),
+                Err(TyError::mk_infinite_type(var, ty)),
             _ => Ok(subst![var => ty]),
         }
     }
 
     /// Finds the most general set of substitutions that can be found for two
     /// types. "MGU" means the most general unifier.
+    ///
+    /// The error type unifies with everything, type variables unified with it
+    /// are bound to it so that they don't cause any further errors.
+    ///
+    /// A numeric type variable only unifies with `int`, `float` and other
+    /// type variables, which are bound to it so that they stay numeric. The
+    /// mismatch with any other type is reported as one with `int`, the type
+    /// it would default to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate type_infer_rs;
+    /// # use type_infer_rs::ty::{Ty, TyVar, subst::Subst};
+    /// let n = Ty::mk_var(TyVar::num(0));
+    /// let a = Ty::mk_var(TyVar::new("a".to_string()));
+    ///
+    /// assert_eq!(
+    ///     Subst::mgu(n.clone(), Ty::mk_float()),
+    ///     Ok(subst![TyVar::num(0) => Ty::mk_float()]),
+    /// );
+    /// assert_eq!(
+    ///     Subst::mgu(n.clone(), a),
+    ///     Ok(subst![TyVar::new("a".to_string()) => n.clone()]),
+    /// );
+    /// assert_eq!(
+    ///     Subst::mgu(n, Ty::mk_bool()).unwrap_err().to_string(),
+    ///     "cannot unify int with bool",
+    /// );
+    /// ```
     pub fn mgu(ty1: Ty, ty2: Ty) -> TyResult<Subst> {
         match (ty1, ty2) {
             (Ty::Arrow(p1, r1), Ty::Arrow(p2, r2)) => {
@@ -141,13 +175,68 @@ impl Subst {
                 let s2 = Subst::mgu(r1.apply(&s1), r2.apply(&s1))?;
                 Ok(s1.compose(&s2))
             },
+            (Ty::Var(var), ty @ Ty::Var(TyVar::Num(_)))
+                | (ty @ Ty::Var(TyVar::Num(_)), Ty::Var(var)) =>
+                Subst::bind(var, ty),
+            (Ty::Var(TyVar::Num(_)), ty) if !ty.may_be_num() =>
+                Err(TyError::mk_mismatch(Ty::mk_int(), ty.default_nums())),
+            (ty, Ty::Var(TyVar::Num(_))) if !ty.may_be_num() =>
+                Err(TyError::mk_mismatch(ty.default_nums(), Ty::mk_int())),
             (Ty::Var(var), ty) | (ty, Ty::Var(var)) =>
                 Subst::bind(var, ty),
-            (Ty::Int, Ty::Int) | (Ty::Bool, Ty::Bool) =>
+            (Ty::Int, Ty::Int) | (Ty::Float, Ty::Float) | (Ty::Bool, Ty::Bool)
+                | (Ty::Char, Ty::Char) | (Ty::String, Ty::String) | (Ty::Unit, Ty::Unit) =>
+                Ok(Subst::identity()),
+            (Ty::Error, _) | (_, Ty::Error) =>
                 Ok(Subst::identity()),
             (ty1, ty2) =>
-                Err(TyError::Unknown(format!("cannot unify {ty1} with {ty2}"))),
+                Err(TyError::mk_mismatch(ty1, ty2)),
+        }
+    }
+
+    /// Finds a substitution that makes a general type equal to a specific
+    /// one, `S(general) = specific`, if there is any. Unlike [`Subst::mgu`],
+    /// only the variables of the general type are substituted, the variables
+    /// of the specific type are taken as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use] extern crate type_infer_rs;
+    /// # use type_infer_rs::ty::{Ty, TyVar, subst::Subst};
+    /// let a = Ty::mk_var(TyVar::new("a".to_string()));
+    /// let b = Ty::mk_var(TyVar::new("b".to_string()));
+    ///
+    /// // a -> a matches int -> int with [a: int]
+    /// assert_eq!(
+    ///     Subst::matching(
+    ///         &Ty::mk_arrow(a.clone(), a.clone()),
+    ///         &Ty::mk_arrow(Ty::mk_int(), Ty::mk_int()),
+    ///     ),
+    ///     Some(subst![TyVar::new("a".to_string()) => Ty::mk_int()]),
+    /// );
+    ///
+    /// // int doesn't match b, although they unify.
+    /// assert_eq!(Subst::matching(&Ty::mk_int(), &b), None);
+    /// ```
+    pub fn matching(general: &Ty, specific: &Ty) -> Option<Subst> {
+        fn go(general: &Ty, specific: &Ty, subst: &mut Subst) -> bool {
+            match (general, specific) {
+                (Ty::Var(var), _) => match subst.mapping.get(var) {
+                    Some(bound) => bound == specific,
+                    None => {
+                        subst.insert(var.clone(), specific.clone());
+                        true
+                    },
+                },
+                (Ty::Arrow(p1, r1), Ty::Arrow(p2, r2)) =>
+                    go(p1, p2, subst) && go(r1, r2, subst),
+                (ty1, ty2) => ty1 == ty2,
+            }
         }
+
+        let mut subst = Subst::identity();
+        if go(general, specific, &mut subst) { Some(subst) } else { None }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&TyVar, &Ty)> {
@@ -166,6 +255,28 @@ impl fmt::Display for Subst {
     }
 }
 
+#[cfg(feature = "serde")]
+mod pairs {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::ty::{Ty, TyVar};
+
+    pub fn serialize<S: Serializer>(
+        mapping: &BTreeMap<TyVar, Ty>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(mapping.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<TyVar, Ty>, D::Error> {
+        Ok(Vec::<(TyVar, Ty)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
 #[macro_export]
 macro_rules! subst {
     ($($var:expr => $ty:expr),* $(,)?) => {{
@@ -8,6 +8,7 @@ use crate::expr::expr::Ident;
 use super::{TyVar, ty_scheme::TyScheme, types::Types, subst::Subst};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TyCtxt {
     types: BTreeMap<Ident, TyScheme>,
 }
@@ -30,11 +31,21 @@ impl TyCtxt {
     pub fn get(&self, var: &Ident) -> Option<&TyScheme> {
         self.types.get(var)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Ident, &TyScheme)> {
+        self.types.iter()
+    }
+}
+
+impl Default for TyCtxt {
+    fn default() -> TyCtxt {
+        TyCtxt::new()
+    }
 }
 
 impl Types for TyCtxt {
     fn ftv(&self) -> BTreeSet<TyVar> {
-        self.types.values().map(|x| x.clone()).collect::<Vec<_>>().ftv()
+        self.types.values().cloned().collect::<Vec<_>>().ftv()
     }
 
     fn apply(&self, subst: &Subst) -> Self {
@@ -1,12 +1,14 @@
 // Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
 // root for license information.
 
-use std::{fmt, collections::BTreeSet};
+use std::{fmt, collections::{BTreeMap, BTreeSet}};
 
 use super::{TyVar, Ty, types::Types, subst::Subst};
+use crate::visit::Visit;
 
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TyScheme {
     pub vars: Vec<TyVar>,
     pub ty: Ty,
@@ -16,6 +18,137 @@ impl TyScheme {
     pub fn mk_forall(vars: Vec<TyVar>, ty: Ty) -> TyScheme {
         TyScheme { vars, ty }
     }
+
+    /// Renames the bound variables to `a`, `b`, `c`, ... in the order they
+    /// appear in the type, and drops the ones that don't appear. Names that
+    /// are free in the scheme are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::ty::{Ty, TyVar, ty_scheme::TyScheme};
+    /// // forall ?3 ?1 ?2 . ?2 -> ?1
+    /// let tys = TyScheme::mk_forall(
+    ///     vec![TyVar::Unknown(3), TyVar::Unknown(1), TyVar::Unknown(2)],
+    ///     Ty::mk_arrow(
+    ///         Ty::mk_var(TyVar::Unknown(2)),
+    ///         Ty::mk_var(TyVar::Unknown(1)),
+    ///     ),
+    /// );
+    ///
+    /// // forall a b . a -> b
+    /// assert_eq!(tys.normalize(), TyScheme::mk_forall(
+    ///     vec![TyVar::new("a".to_string()), TyVar::new("b".to_string())],
+    ///     Ty::mk_arrow(
+    ///         Ty::mk_var(TyVar::new("a".to_string())),
+    ///         Ty::mk_var(TyVar::new("b".to_string())),
+    ///     ),
+    /// ));
+    /// ```
+    pub fn normalize(&self) -> TyScheme {
+        self.rename_bound(&self.ftv())
+    }
+
+    /// Decides whether two schemes are equal up to the names of their bound
+    /// variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::ty::{Ty, TyVar, ty_scheme::TyScheme};
+    /// let a = TyVar::new("a".to_string());
+    /// let b = TyVar::new("b".to_string());
+    ///
+    /// // forall a . a -> a  =α  forall b . b -> b
+    /// assert!(
+    ///     TyScheme::mk_forall(vec![a.clone()], Ty::mk_arrow(Ty::mk_var(a.clone()), Ty::mk_var(a.clone())))
+    ///         .alpha_eq(&TyScheme::mk_forall(vec![b.clone()], Ty::mk_arrow(Ty::mk_var(b.clone()), Ty::mk_var(b.clone())))),
+    /// );
+    ///
+    /// // forall a . a -> b  ≠α  forall b . b -> b
+    /// assert!(
+    ///     !TyScheme::mk_forall(vec![a.clone()], Ty::mk_arrow(Ty::mk_var(a.clone()), Ty::mk_var(b.clone())))
+    ///         .alpha_eq(&TyScheme::mk_forall(vec![b.clone()], Ty::mk_arrow(Ty::mk_var(b.clone()), Ty::mk_var(b.clone())))),
+    /// );
+    /// ```
+    pub fn alpha_eq(&self, other: &TyScheme) -> bool {
+        self.ftv() == other.ftv() && self.normalize() == other.normalize()
+    }
+
+    /// Decides whether this scheme is an instance of a more general one,
+    /// `σ′ ⊑ σ`, the relation of the `Inst` rule. That is, if the type of
+    /// `σ′` is the type of `σ` with its bound variables substituted, and the
+    /// bound variables of `σ′` are not free in `σ`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use type_infer_rs::ty::{Ty, TyVar, ty_scheme::TyScheme};
+    /// let a = TyVar::new("a".to_string());
+    /// let b = TyVar::new("b".to_string());
+    /// // forall a b . a -> b -> a
+    /// let k = TyScheme::mk_forall(
+    ///     vec![a.clone(), b.clone()],
+    ///     Ty::mk_arrow(Ty::mk_var(a.clone()), Ty::mk_arrow(Ty::mk_var(b.clone()), Ty::mk_var(a.clone()))),
+    /// );
+    /// // forall a . a -> a -> a
+    /// let k_same = TyScheme::mk_forall(
+    ///     vec![a.clone()],
+    ///     Ty::mk_arrow(Ty::mk_var(a.clone()), Ty::mk_arrow(Ty::mk_var(a.clone()), Ty::mk_var(a.clone()))),
+    /// );
+    ///
+    /// assert!(k_same.is_instance_of(&k));
+    /// assert!(!k.is_instance_of(&k_same));
+    /// ```
+    pub fn is_instance_of(&self, general: &TyScheme) -> bool {
+        // The bound variables of the instance are rigid, they are renamed
+        // apart from every variable of the general scheme.
+        let avoid = general.ty.ftv()
+            .union(&self.ftv())
+            .cloned()
+            .collect();
+        let instance = self.rename_bound(&avoid);
+
+        match Subst::matching(&general.ty, &instance.ty) {
+            Some(subst) => subst.iter().all(|(var, ty)| {
+                general.vars.contains(var) || ty == &Ty::mk_var(var.clone())
+            }),
+            None => false,
+        }
+    }
+
+    /// Renames the bound variables to `a`, `b`, `c`, ... in the order they
+    /// appear, skipping the names to avoid.
+    fn rename_bound(&self, avoid: &BTreeSet<TyVar>) -> TyScheme {
+        /// The bound variables in the order they appear.
+        struct Order<'a> {
+            bound: &'a [TyVar],
+            order: Vec<TyVar>,
+        }
+
+        impl Visit for Order<'_> {
+            fn visit_ty_var(&mut self, var: &TyVar) {
+                if self.bound.contains(var) && !self.order.contains(var) {
+                    self.order.push(var.clone());
+                }
+            }
+        }
+
+        let mut order = Order { bound: &self.vars, order: Vec::new() };
+        order.visit_ty(&self.ty);
+        let order = order.order;
+        let fresh = (0..)
+            .map(|i: usize| match i / 26 {
+                0 => TyVar::new(((b'a' + i as u8) as char).to_string()),
+                n => TyVar::new(format!("{}{}", (b'a' + (i % 26) as u8) as char, n)),
+            })
+            .filter(|name| !avoid.contains(name));
+        let vars = fresh.take(order.len()).collect::<Vec<_>>();
+        let names = order.into_iter()
+            .zip(vars.iter().cloned())
+            .collect::<BTreeMap<_, _>>();
+        TyScheme::mk_forall(vars, self.ty.rename(&names))
+    }
 }
 
 impl Types for TyScheme {
@@ -77,6 +210,9 @@ impl Types for TyScheme {
 
 impl fmt::Display for TyScheme {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.vars.is_empty() {
+            return write!(f, "{}", self.ty);
+        }
         write!(f, "forall {} . {}",
             self.vars.iter()
                 .map(|var| format!("{}", var))
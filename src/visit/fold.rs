@@ -0,0 +1,66 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use crate::{expr::expr::{Expr, ExprKind}, ty::Ty};
+
+/// A traversal of a tree by value, which rebuilds the tree from the folded
+/// children. The ids of expressions are kept.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{ty::{Ty, TyVar}, visit::fold::{self, Fold}};
+/// /// Replaces every type variable with `int`.
+/// struct Ground;
+///
+/// impl Fold for Ground {
+///     fn fold_ty(&mut self, ty: Ty) -> Ty {
+///         match ty {
+///             Ty::Var(_) => Ty::mk_int(),
+///             ty => fold::walk_ty(self, ty),
+///         }
+///     }
+/// }
+///
+/// let a = Ty::mk_var(TyVar::new("a".to_string()));
+/// let ty = Ground.fold_ty(Ty::mk_arrow(a.clone(), Ty::mk_arrow(Ty::mk_bool(), a)));
+/// assert_eq!(ty.to_string(), "int -> bool -> int");
+/// ```
+pub trait Fold {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_expr(self, expr)
+    }
+
+    fn fold_ty(&mut self, ty: Ty) -> Ty {
+        walk_ty(self, ty)
+    }
+}
+
+pub fn walk_expr<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    let kind = match expr.kind {
+        kind @ (ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_)) => kind,
+        ExprKind::App { callee, arg } => ExprKind::App {
+            callee: Box::new(folder.fold_expr(*callee)),
+            arg: Box::new(folder.fold_expr(*arg)),
+        },
+        ExprKind::Abs { param, body } => ExprKind::Abs {
+            param,
+            body: Box::new(folder.fold_expr(*body)),
+        },
+        ExprKind::Let { name, value, body } => ExprKind::Let {
+            name,
+            value: Box::new(folder.fold_expr(*value)),
+            body: Box::new(folder.fold_expr(*body)),
+        },
+    };
+    Expr { kind, id: expr.id }
+}
+
+pub fn walk_ty<F: Fold + ?Sized>(folder: &mut F, ty: Ty) -> Ty {
+    match ty {
+        Ty::Arrow(param_ty, ret_ty) =>
+            Ty::mk_arrow(folder.fold_ty(*param_ty), folder.fold_ty(*ret_ty)),
+        ty @ (Ty::Var(_) | Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String | Ty::Unit
+            | Ty::Error) => ty,
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+//! Traversals of expressions and types. [`Visit`] walks a tree by reference,
+//! [`visit_mut::VisitMut`] walks it by mutable reference, and [`fold::Fold`]
+//! rebuilds it by value.
+//!
+//! Every method defaults to a walk function which visits the children of the
+//! node. An implementation overrides the methods of the nodes it's interested
+//! in, and calls the walk function itself to go on into the children.
+
+pub mod visit_mut;
+pub mod fold;
+
+use crate::{expr::expr::{Expr, ExprKind}, ty::{Ty, TyVar}};
+
+/// A traversal of a tree by reference.
+///
+/// # Examples
+///
+/// ```
+/// # use type_infer_rs::{expr::expr::{Expr, ExprKind, Ident}, visit::{self, Visit}};
+/// /// Counts the `λ`s of an expression.
+/// struct Lambdas(usize);
+///
+/// impl Visit for Lambdas {
+///     fn visit_expr(&mut self, expr: &Expr) {
+///         if let ExprKind::Abs { .. } = expr.kind {
+///             self.0 += 1;
+///         }
+///         visit::walk_expr(self, expr);
+///     }
+/// }
+///
+/// // λ x. λ y. x
+/// let x = Ident::new("x".to_string());
+/// let expr = Expr::mk_abs(x.clone(), Expr::mk_abs(Ident::new("y".to_string()), Expr::mk_var(x)));
+/// let mut lambdas = Lambdas(0);
+/// lambdas.visit_expr(&expr);
+/// assert_eq!(lambdas.0, 2);
+/// ```
+pub trait Visit {
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_ty(&mut self, ty: &Ty) {
+        walk_ty(self, ty)
+    }
+
+    fn visit_ty_var(&mut self, _var: &TyVar) {}
+}
+
+pub fn walk_expr<V: Visit + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match &expr.kind {
+        ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) => {},
+        ExprKind::App { callee, arg } => {
+            visitor.visit_expr(callee);
+            visitor.visit_expr(arg);
+        },
+        ExprKind::Abs { body, .. } => visitor.visit_expr(body),
+        ExprKind::Let { value, body, .. } => {
+            visitor.visit_expr(value);
+            visitor.visit_expr(body);
+        },
+    }
+}
+
+pub fn walk_ty<V: Visit + ?Sized>(visitor: &mut V, ty: &Ty) {
+    match ty {
+        Ty::Var(var) => visitor.visit_ty_var(var),
+        Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String | Ty::Unit | Ty::Error => {},
+        Ty::Arrow(param_ty, ret_ty) => {
+            visitor.visit_ty(param_ty);
+            visitor.visit_ty(ret_ty);
+        },
+    }
+}
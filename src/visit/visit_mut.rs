@@ -0,0 +1,44 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+
+use crate::{expr::expr::{Expr, ExprKind}, ty::{Ty, TyVar}};
+
+/// A traversal of a tree by mutable reference, which updates the nodes in
+/// place.
+pub trait VisitMut {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_ty_mut(&mut self, ty: &mut Ty) {
+        walk_ty(self, ty)
+    }
+
+    fn visit_ty_var_mut(&mut self, _var: &mut TyVar) {}
+}
+
+pub fn walk_expr<V: VisitMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match &mut expr.kind {
+        ExprKind::Var(_) | ExprKind::Lit(_) | ExprKind::Hole(_) => {},
+        ExprKind::App { callee, arg } => {
+            visitor.visit_expr_mut(callee);
+            visitor.visit_expr_mut(arg);
+        },
+        ExprKind::Abs { body, .. } => visitor.visit_expr_mut(body),
+        ExprKind::Let { value, body, .. } => {
+            visitor.visit_expr_mut(value);
+            visitor.visit_expr_mut(body);
+        },
+    }
+}
+
+pub fn walk_ty<V: VisitMut + ?Sized>(visitor: &mut V, ty: &mut Ty) {
+    match ty {
+        Ty::Var(var) => visitor.visit_ty_var_mut(var),
+        Ty::Int | Ty::Float | Ty::Bool | Ty::Char | Ty::String | Ty::Unit | Ty::Error => {},
+        Ty::Arrow(param_ty, ret_ty) => {
+            visitor.visit_ty_mut(param_ty);
+            visitor.visit_ty_mut(ret_ty);
+        },
+    }
+}
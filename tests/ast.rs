@@ -0,0 +1,63 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// The arena AST must hold the same tree as the boxed expression it was
+// converted from.
+
+use type_infer_rs::{
+    expr::{ast::{Ast, NodeKind}, expr::{Expr, Ident}},
+    gen::Gen,
+    syntax::parser::parse,
+    ty::ty_ctxt::TyCtxt,
+};
+
+#[test]
+fn conversion_round_trips() {
+    let mut gen = Gen::new(0x5851_f42d_4c95_7f2d);
+    for _ in 0..200 {
+        let ty = gen.gen_ty(4);
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &ty, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        let ast = Ast::from_expr(&expr);
+        assert_eq!(ast.to_expr(ast.root()), expr);
+
+        // Every node is the parent of its children, and only the root has
+        // no parent.
+        for node in ast.iter() {
+            for child in ast.children(node.id) {
+                assert_eq!(ast.parent(child), Some(node.id));
+            }
+            assert_eq!(node.parent.is_none(), node.id == ast.root());
+        }
+    }
+}
+
+#[test]
+fn variables_find_their_binders() {
+    // The `x` in the value of the inner `let` is the parameter, the one in
+    // its body is the inner `let`.
+    let src = "λ x. let x = x in x";
+    let ast = Ast::from_parsed(&parse(src).unwrap());
+
+    let binder_of = |offset| {
+        let binder = ast.binder(ast.enclosing(offset).unwrap()).unwrap();
+        (ast[binder].binder_span.unwrap().lo, ast[binder].kind.clone())
+    };
+    let (lo, kind) = binder_of(src.find("= x").unwrap() + 2);
+    assert_eq!(lo, src.find('x').unwrap());
+    assert!(matches!(kind, NodeKind::Abs { .. }));
+    let (lo, kind) = binder_of(src.len());
+    assert_eq!(lo, src.find("x =").unwrap());
+    assert!(matches!(kind, NodeKind::Let { .. }));
+
+    let unbound = Ast::from_parsed(&parse("λ x. y").unwrap());
+    assert_eq!(unbound.binder(unbound.enclosing(5).unwrap()), None);
+}
+
+#[test]
+#[should_panic(expected = "not assigned")]
+fn unassigned_ids_are_rejected() {
+    Ast::from_expr(&Expr::mk_var(Ident::new("x".to_string())));
+}
@@ -0,0 +1,26 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Derivation trees rendered as LaTeX must compile, whatever the names and
+// literals in them.
+
+use type_infer_rs::{infer::InferCtxt, syntax::parser::parse};
+
+fn latex(src: &str) -> String {
+    let expr = parse(src).unwrap().expr;
+    InferCtxt::new().derive(&expr).unwrap().to_latex()
+}
+
+#[test]
+fn special_characters_are_escaped() {
+    let out = latex(r#"(λ x_1. x_1) "a$b{%#&}^~""#);
+    assert!(out.contains(r"\mathit{x\_1}"), "{}", out);
+    assert!(
+        out.contains(r#"\texttt{"a\$b\{\%\#\&\}\textasciicircum{}\textasciitilde{}"}"#),
+        "{}", out,
+    );
+
+    let out = latex(r"'\\'");
+    assert!(out.contains(r"\texttt{'\textbackslash{}\textbackslash{}'}"), "{}", out);
+    assert!(out.contains(r"\UnaryInfC{$\cdot \vdash \texttt{"), "{}", out);
+}
@@ -0,0 +1,65 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Well-typed programs don't go wrong: random programs that type check must
+// evaluate to a value of the inferred type.
+
+use type_infer_rs::{
+    eval::{eval, Value},
+    gen::Gen,
+    infer::InferCtxt,
+    syntax::parser::parse,
+    ty::Ty,
+};
+
+fn value_has_type(value: &Value, ty: &Ty) -> bool {
+    matches!(
+        (value, ty),
+        (_, Ty::Var(_))
+            | (Value::Int(_), Ty::Int)
+            | (Value::Float(_), Ty::Float)
+            | (Value::Bool(_), Ty::Bool)
+            | (Value::Char(_), Ty::Char)
+            | (Value::String(_), Ty::String)
+            | (Value::Unit, Ty::Unit)
+            | (Value::Closure { .. } | Value::Prim { .. }, Ty::Arrow(_, _))
+    )
+}
+
+#[test]
+fn well_typed_programs_do_not_go_wrong() {
+    let mut gen = Gen::new(0x2545_f491_4f6c_dd1d);
+    let mut well_typed = 0;
+
+    for _ in 0..5000 {
+        let expr = gen.gen_untyped(6).assign_ids();
+        let recovered = InferCtxt::new().infer_recovering(&expr);
+        if !recovered.errors.is_empty() {
+            continue;
+        }
+        let ty = recovered.ty.clone();
+        well_typed += 1;
+        match eval(&recovered.resolve_lits(&expr)) {
+            Ok(value) => assert!(
+                value_has_type(&value, &ty),
+                "{} evaluates to {}, which is not of type {}", expr, value, ty,
+            ),
+            Err(err) => panic!("{} : {} goes wrong: {}", expr, ty, err),
+        }
+    }
+
+    assert!(well_typed > 500, "only {} programs were well-typed", well_typed);
+}
+
+#[test]
+fn integer_literals_evaluate_at_their_type() {
+    // `1` is a float, because `i` is also applied to `2.5`.
+    let expr = parse("(λ i. (λ u. i 1) (i 2.5)) (λ x. x)").unwrap().expr;
+    let recovered = InferCtxt::new().infer_recovering(&expr);
+    assert_eq!(recovered.ty, Ty::mk_float());
+    assert_eq!(eval(&recovered.resolve_lits(&expr)), Ok(Value::Float(1.0)));
+
+    let expr = parse("(λ i. i 1) (λ x. x)").unwrap().expr;
+    let recovered = InferCtxt::new().infer_recovering(&expr);
+    assert_eq!(eval(&recovered.resolve_lits(&expr)), Ok(Value::Int(1)));
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Properties of inference on randomly generated well-typed expressions.
+
+use std::collections::BTreeMap;
+
+use type_infer_rs::{
+    expr::expr::{Expr, ExprKind, Ident},
+    gen::{minimize, Gen},
+    infer::InferCtxt,
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, subst::Subst},
+};
+
+fn var(name: &str) -> Ty {
+    Ty::mk_var(TyVar::new(name.to_string()))
+}
+
+/// Random closed types, and a few polymorphic ones.
+fn targets(gen: &mut Gen) -> Vec<Ty> {
+    let mut targets = (0..200).map(|_| gen.gen_ty(4)).collect::<Vec<_>>();
+    targets.push(Ty::mk_arrow(var("a"), var("a")));
+    targets.push(Ty::mk_arrow(var("a"), Ty::mk_arrow(var("b"), var("a"))));
+    targets.push(Ty::mk_arrow(
+        Ty::mk_arrow(var("a"), var("b")),
+        Ty::mk_arrow(var("a"), var("b")),
+    ));
+    targets
+}
+
+/// The inferred type of an expression is at least as general as the type it
+/// was generated for, `target` is an instance of it.
+fn at_least_as_general(expr: &Expr, target: &Ty) -> bool {
+    match InferCtxt::new().infer(&expr.clone().assign_ids()) {
+        Ok(ty) => Subst::matching(&ty, target).is_some(),
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn inferred_type_is_at_least_as_general_as_target() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    let mut generated = 0;
+    for target in targets(&mut gen) {
+        for size in [2, 6, 12] {
+            let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, size) else {
+                continue;
+            };
+            if !at_least_as_general(&expr, &target) {
+                let smallest = minimize(expr, |e| !at_least_as_general(e, &target));
+                panic!("{} does not have a type as general as {}", smallest, target);
+            }
+            generated += 1;
+        }
+    }
+    assert!(generated > 300, "only {} expressions were generated", generated);
+}
+
+/// Renames every bound variable to a new unique name.
+fn rename(expr: &Expr, scope: &mut BTreeMap<Ident, Ident>, next: &mut u32) -> Expr {
+    fn fresh(name: &Ident, next: &mut u32) -> (Ident, Ident) {
+        *next += 1;
+        (name.clone(), Ident::new(format!("renamed{}", next)))
+    }
+    match &expr.kind {
+        ExprKind::Var(name) =>
+            Expr::mk_var(scope.get(name).cloned().unwrap_or_else(|| name.clone())),
+        ExprKind::Lit(_) | ExprKind::Hole(_) => Expr::new(expr.kind.clone()),
+        ExprKind::App { callee, arg } => Expr::mk_app(
+            rename(callee, scope, next),
+            rename(arg, scope, next),
+        ),
+        ExprKind::Abs { param, body } => {
+            let (old, new) = fresh(param, next);
+            let shadowed = scope.insert(old.clone(), new.clone());
+            let body = rename(body, scope, next);
+            match shadowed {
+                Some(shadowed) => scope.insert(old, shadowed),
+                None => scope.remove(&old),
+            };
+            Expr::mk_abs(new, body)
+        },
+        ExprKind::Let { name, value, body } => {
+            let value = rename(value, scope, next);
+            let (old, new) = fresh(name, next);
+            let shadowed = scope.insert(old.clone(), new.clone());
+            let body = rename(body, scope, next);
+            match shadowed {
+                Some(shadowed) => scope.insert(old, shadowed),
+                None => scope.remove(&old),
+            };
+            Expr::mk_let(new, value, body)
+        },
+    }
+}
+
+#[test]
+fn inference_is_invariant_under_alpha_renaming() {
+    let mut gen = Gen::new(0x2545_f491_4f6c_dd1d);
+    for target in targets(&mut gen) {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 8) else {
+            continue;
+        };
+        let renamed = rename(&expr, &mut BTreeMap::new(), &mut 0);
+        assert_eq!(
+            InferCtxt::new().infer(&expr.clone().assign_ids()),
+            InferCtxt::new().infer(&renamed.clone().assign_ids()),
+            "{} and {} have different types", expr, renamed,
+        );
+    }
+}
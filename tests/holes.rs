@@ -0,0 +1,89 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Typed holes report the type their context expects, and the bindings in
+// scope that fit it.
+
+use type_infer_rs::{
+    hole::HoleReport,
+    infer::InferCtxt,
+    prelude,
+    syntax::parser::{parse, parse_signature},
+    ty::ty_ctxt::TyCtxt,
+};
+
+fn tcx() -> TyCtxt {
+    parse_signature("
+        val concat : string -> string -> string;
+        val length : string -> int;
+        val id : forall a . a -> a;
+    ").unwrap()
+}
+
+fn infer_holes(tcx: &TyCtxt, src: &str) -> Vec<HoleReport> {
+    let expr = parse(src).unwrap().expr;
+    InferCtxt::new().infer_holes_in(tcx, &expr).unwrap().1
+}
+
+/// The names of the bindings that fit a hole.
+fn fits(hole: &HoleReport) -> Vec<&str> {
+    hole.fits.iter().map(|(name, _)| name.name.as_str()).collect()
+}
+
+#[test]
+fn named_and_anonymous_holes() {
+    let holes = infer_holes(&tcx(), "λ s. concat (_prefix (concat s \"\")) _");
+    assert_eq!(holes.len(), 2);
+    assert_eq!(holes[0].to_string(), "hole _prefix : string -> string\n  id : forall a . a -> a");
+    assert_eq!(holes[1].name, None);
+    assert_eq!(holes[1].ty.to_string(), "string");
+    assert_eq!(fits(&holes[1]), ["s"]);
+}
+
+#[test]
+fn holes_see_the_bindings_in_their_scope() {
+    let holes = infer_holes(&tcx(), "λ s. (λ n. _inner) (length s) _outer");
+    let [inner, outer] = &holes[..] else {
+        panic!("expected two holes, found {:?}", holes);
+    };
+    // The inner hole is applied to `_outer`, so `n` and `s` don't fit it,
+    // and `n` is not in the scope of the outer one.
+    assert_eq!(inner.ty.to_string(), "?6 -> ?1");
+    assert_eq!(fits(inner), ["concat", "id", "length"]);
+    assert_eq!(outer.ty.to_string(), "?6");
+    assert_eq!(fits(outer), ["concat", "id", "length", "s"]);
+
+    // A parameter shadows the binding of the context. The hole is its
+    // argument, so it can't be the parameter itself.
+    let holes = infer_holes(&tcx(), "λ length. length _");
+    assert_eq!(holes[0].ty.to_string(), "?2");
+    assert_eq!(fits(&holes[0]), ["concat", "id"]);
+}
+
+#[test]
+fn candidates_that_dont_fit_are_rejected() {
+    // `x` and `n` are monomorphic with unknown types, the hole can't decide
+    // what they are.
+    let holes = infer_holes(&tcx(), "λ x. λ n. concat _ \"a\"");
+    assert_eq!(holes[0].ty.to_string(), "string");
+    assert!(holes[0].fits.is_empty());
+
+    // `length` has the wrong result type, `concat` one argument too many.
+    let holes = infer_holes(&tcx(), "λ u. concat (_f u) \"a\"");
+    assert_eq!(holes[0].ty.to_string(), "?0 -> string");
+    assert_eq!(fits(&holes[0]), ["id"]);
+}
+
+#[test]
+fn holes_are_fitted_from_the_prelude() {
+    let holes = infer_holes(prelude::tcx(), "fadd (_ 1.5) 2.5");
+    assert_eq!(holes[0].ty.to_string(), "float -> float");
+    assert_eq!(fits(&holes[0]), ["id", "negate"]);
+
+    // Checking without holes in between doesn't leave holes behind.
+    let mut icx = InferCtxt::new();
+    icx.infer_in(prelude::tcx(), &parse("λ x. _a").unwrap().expr).unwrap();
+    let (_, holes) = icx.infer_holes(&parse("_b").unwrap().expr).unwrap();
+    assert_eq!(holes.len(), 1);
+    assert_eq!(holes[0].to_string(), "hole _b : ?2");
+}
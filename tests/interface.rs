@@ -0,0 +1,73 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Interface files written by checking a module seed the checking of the
+// modules importing it.
+
+use std::collections::BTreeMap;
+
+use type_infer_rs::{
+    error::InterfaceError,
+    expr::expr::Ident,
+    interface::{Interface, VERSION},
+    syntax::parser::parse_module,
+};
+
+fn name(name: &str) -> Ident {
+    Ident::new(name.to_string())
+}
+
+#[test]
+fn interfaces_seed_importers() {
+    let dir = std::env::temp_dir().join(format!("type-infer-rs-interface-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("combinators.tyi");
+
+    let src = "let k = λ x. λ y. x; let s = λ f. λ g. λ x. f x (g x);";
+    let exports = parse_module(name("combinators"), src).unwrap().check(&BTreeMap::new()).unwrap();
+    let interface = Interface::new(name("combinators"), src, exports);
+    interface.save(&path).unwrap();
+
+    let loaded = Interface::load(&path, src, &BTreeMap::new()).unwrap();
+    assert_eq!(loaded, interface);
+    let main_src = "import combinators; let i = s k k;";
+    let main = parse_module(name("main"), main_src).unwrap();
+    let exports = main.check(&BTreeMap::from([(loaded.module.clone(), loaded.exports.clone())])).unwrap();
+    assert_eq!(exports.get(&name("i")).unwrap().to_string(), "forall a . a -> a");
+    let imports = BTreeMap::from([(name("combinators"), loaded)]);
+    let main_path = dir.join("main.tyi");
+    Interface::new(name("main"), main_src, exports).with_imports(&imports).save(&main_path).unwrap();
+    assert!(Interface::load(&main_path, main_src, &imports).is_ok());
+
+    // Editing the source makes the file stale, and so the files of the
+    // modules importing it.
+    let edited = "let k = λ x. λ y. y; let s = λ f. λ g. λ x. f x (g x);";
+    assert!(matches!(
+        Interface::load(&path, edited, &BTreeMap::new()),
+        Err(InterfaceError::Stale(module)) if module == name("combinators"),
+    ));
+    let exports = parse_module(name("combinators"), edited).unwrap().check(&BTreeMap::new()).unwrap();
+    let edited_imports = BTreeMap::from([(name("combinators"), Interface::new(name("combinators"), edited, exports))]);
+    assert!(matches!(
+        Interface::load(&main_path, main_src, &edited_imports),
+        Err(InterfaceError::Stale(module)) if module == name("main"),
+    ));
+    assert!(matches!(Interface::load(&main_path, main_src, &BTreeMap::new()), Err(InterfaceError::Stale(_))));
+    assert!(matches!(Interface::load(dir.join("missing.tyi"), src, &BTreeMap::new()), Err(InterfaceError::Io(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn malformed_interfaces_are_rejected() {
+    let src = "let n = 1;";
+    let text = Interface::new(name("n"), src, Default::default()).to_string();
+    assert!(text.starts_with(&format!("(interface {} n ", VERSION)));
+
+    let future = text.replacen(&VERSION.to_string(), "99", 1);
+    let no_imports = BTreeMap::new();
+    assert!(matches!(Interface::read(&future, src, &no_imports), Err(InterfaceError::Version(99))));
+    let truncated = format!("(interface {} n)", VERSION);
+    assert!(matches!(Interface::read(&truncated, src, &no_imports), Err(InterfaceError::Parse(_))));
+    assert!(matches!(Interface::read("(ctxt)", src, &no_imports), Err(InterfaceError::Parse(_))));
+}
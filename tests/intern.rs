@@ -0,0 +1,113 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Inference on interned types must agree with inference on boxed types.
+
+use type_infer_rs::{
+    expr::{expr::{Expr, Ident}, lit::Lit},
+    gen::Gen,
+    infer::InferCtxt,
+    intern::{self, TyArena, subst::Subst},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt},
+};
+
+fn var(name: &str) -> Ty {
+    Ty::mk_var(TyVar::new(name.to_string()))
+}
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name.to_string())
+}
+
+#[test]
+fn interned_inference_agrees_on_well_typed_expressions() {
+    let mut gen = Gen::new(0x2545_f491_4f6c_dd1d);
+    let mut targets = (0..300).map(|_| gen.gen_ty(4)).collect::<Vec<_>>();
+    targets.push(Ty::mk_arrow(var("a"), var("a")));
+    targets.push(Ty::mk_arrow(var("a"), Ty::mk_arrow(var("b"), var("a"))));
+
+    for target in targets {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        assert_eq!(
+            intern::infer::InferCtxt::new().infer(&expr),
+            InferCtxt::new().infer(&expr),
+            "{}", expr,
+        );
+    }
+}
+
+#[test]
+fn interned_inference_agrees_on_type_errors() {
+    let x = ident("x");
+    let exprs = [
+        // λ x. x x
+        Expr::mk_abs(x.clone(), Expr::mk_app(Expr::mk_var(x.clone()), Expr::mk_var(x.clone()))),
+        // 1 true
+        Expr::mk_app(Expr::mk_lit(Lit::Int(1)), Expr::mk_lit(Lit::Bool(true))),
+        // (λ x. x 1) true
+        Expr::mk_app(
+            Expr::mk_abs(x.clone(), Expr::mk_app(Expr::mk_var(x.clone()), Expr::mk_lit(Lit::Int(1)))),
+            Expr::mk_lit(Lit::Bool(true)),
+        ),
+        // let f = λ x. x in g
+        Expr::mk_let(ident("f"), Expr::mk_abs(x.clone(), Expr::mk_var(x)), Expr::mk_var(ident("g"))),
+    ];
+
+    for expr in exprs {
+        let expr = expr.assign_ids();
+        let expected = InferCtxt::new().infer(&expr).unwrap_err();
+        let found = intern::infer::InferCtxt::new().infer(&expr).unwrap_err();
+        assert_eq!(found.kind, expected.kind, "{}", expr);
+    }
+}
+
+#[test]
+fn interned_inference_agrees_on_random_programs() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    let mut well_typed = 0;
+    for _ in 0..3000 {
+        let expr = gen.gen_untyped(6).assign_ids();
+        match (intern::infer::InferCtxt::new().infer(&expr), InferCtxt::new().infer(&expr)) {
+            (Ok(found), Ok(expected)) => {
+                assert_eq!(found, expected, "{}", expr);
+                well_typed += 1;
+            },
+            (Err(found), Err(expected)) => assert_eq!(found.kind, expected.kind, "{}", expr),
+            (found, expected) => panic!("{} is inferred as {:?} but {:?} interned", expr, expected, found),
+        }
+    }
+    // Both outcomes are exercised.
+    assert!(well_typed > 100 && well_typed < 2900, "{} programs are well-typed", well_typed);
+}
+
+#[test]
+fn types_are_shared() {
+    let mut arena = TyArena::new();
+    let ty = Ty::mk_arrow(
+        Ty::mk_arrow(var("a"), Ty::mk_int()),
+        Ty::mk_arrow(var("a"), Ty::mk_int()),
+    );
+    let id = arena.from_ty(&ty);
+    assert_eq!(arena.to_ty(id), ty);
+
+    // Interning the same type again adds nothing.
+    let len = arena.len();
+    assert_eq!(arena.from_ty(&ty), id);
+    assert_eq!(arena.len(), len);
+
+    // Unifying a type with itself doesn't bind anything.
+    assert_eq!(Subst::mgu(&mut arena, id, id), Ok(Subst::identity()));
+
+    let a = arena.from_ty(&var("a"));
+    let mut subst = Subst::identity();
+    subst.insert(TyVar::new("a".to_string()), arena.bool());
+    let applied = subst.apply(&mut arena, id);
+    assert_eq!(
+        arena.to_ty(applied).to_string(),
+        "(bool -> int) -> bool -> int",
+    );
+    assert_eq!(subst.apply(&mut arena, a), arena.bool());
+}
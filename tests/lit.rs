@@ -0,0 +1,72 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Literals of every base type, from the source text to values.
+
+use type_infer_rs::{
+    eval::{eval, eval_in, Value},
+    expr::lit::Lit,
+    infer::InferCtxt,
+    prelude,
+    syntax::parser::parse,
+};
+
+fn infer(src: &str) -> String {
+    let expr = parse(src).unwrap().expr;
+    match InferCtxt::new().infer_in(prelude::tcx(), &expr) {
+        Ok(ty) => ty.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn literals_have_base_types() {
+    assert_eq!(infer("9000000000"), "int");
+    assert_eq!(infer("1.25"), "float");
+    assert_eq!(infer("'x'"), "char");
+    assert_eq!(infer(r#""hello""#), "string");
+    assert_eq!(infer("()"), "unit");
+    assert_eq!(infer("λ x. ()"), "?0 -> unit");
+    assert_eq!(infer("concat 1.5"), "cannot unify string with float");
+    assert_eq!(infer("'a' 'b'"), "cannot unify char with char -> ?0\nnote: the callee has type `char`, it is not a function");
+
+    // A `.` after an integer is only a decimal point before a digit.
+    assert_eq!(infer("λ x. 1"), "?0 -> int");
+    assert!(parse("99999999999999999999").is_err());
+}
+
+#[test]
+fn literals_print_as_they_parse() {
+    for src in ["42", "9000000000", "0.5", "1e100", "2.5e-7", "'\\''", "'\\n'", "'λ'", "'\\u{7f}'",
+                r#""a \"quoted\"\tstring\\""#, r#""""#, "()"] {
+        let expr = parse(src).unwrap().expr;
+        assert_eq!(expr.to_string(), src);
+    }
+    assert_eq!(parse("1E+3").unwrap().expr.to_string(), "1000.0");
+    // An `e` without digits is a variable.
+    assert_eq!(parse("1 e").unwrap().expr.to_string(), "(1 e)");
+    assert_eq!(Lit::Float(2.0).to_string(), "2.0");
+    assert_eq!(Lit::Float(1e100).to_string(), "1e100");
+    // An infinite float would print as `inf`, a variable, so it is no literal.
+    assert_eq!(parse("1e999").unwrap_err().message, "float literal `1e999` is out of range");
+
+    assert!(parse("'ab'").is_err());
+    assert!(parse("''").is_err());
+    assert!(parse(r#""unterminated"#).is_err());
+    assert!(parse(r#""\q""#).is_err());
+    assert!(parse(r#""\u{110000}""#).is_err());
+}
+
+#[test]
+fn primitives_evaluate() {
+    let run = |src| eval_in(prelude::env(), &parse(src).unwrap().expr);
+    assert_eq!(run(r#"concat "type " "infer""#), Ok(Value::String("type infer".to_string())));
+    assert_eq!(run("fdiv (fmul 3.0 (fadd 1.0 1.0)) 4.0"), Ok(Value::Float(1.5)));
+    assert_eq!(run("sum (cons 1.5 (cons 2.5 nil))"), Ok(Value::Float(4.0)));
+    assert_eq!(run("negate 2.0"), Ok(Value::Float(-2.0)));
+    assert_eq!(infer("sum (map (λ x. 1.0) (cons 'a' nil))"), "float");
+
+    // A primitive applied to one argument waits for the other.
+    assert_eq!(run(r#"concat "a""#).unwrap().to_string(), "<primitive concat>");
+    assert_eq!(eval(&parse("()").unwrap().expr), Ok(Value::Unit));
+}
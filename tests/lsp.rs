@@ -0,0 +1,118 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Drives the language server through a scripted session over stdio.
+
+use std::{io::{BufRead, BufReader, Write}, process::{Command, Stdio}};
+
+fn frame(content: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+}
+
+fn read_frame(output: &mut impl BufRead) -> String {
+    let mut len = 0;
+    loop {
+        let mut line = String::new();
+        output.read_line(&mut line).unwrap();
+        match line.trim_end().strip_prefix("Content-Length: ") {
+            Some(n) => len = n.parse().unwrap(),
+            None if line.trim_end().is_empty() => break,
+            None => {},
+        }
+    }
+    let mut content = vec![0; len];
+    output.read_exact(&mut content).unwrap();
+    String::from_utf8(content).unwrap()
+}
+
+#[test]
+fn scripted_session() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_type-infer-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = server.stdin.take().unwrap();
+    let mut output = BufReader::new(server.stdout.take().unwrap());
+
+    let mut send = |content: &str| input.write_all(frame(content).as_bytes()).unwrap();
+
+    send(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#);
+    assert!(read_frame(&mut output).contains(r#""hoverProvider":true"#));
+
+    // let id = \x. x in id (1 2)
+    send(r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a","languageId":"ml","version":1,"text":"let id = \\x. x in id (1 2)"}}}"#);
+    let diagnostics = read_frame(&mut output);
+    assert!(diagnostics.contains("cannot unify int with int -> ?"));
+    assert!(diagnostics.contains(r#""start":{"line":0,"character":22}"#));
+
+    send(r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a","version":2},"contentChanges":[{"text":"let id = \\x. x in id 1"}]}}"#);
+    assert!(read_frame(&mut output).contains(r#""diagnostics":[]"#));
+
+    // The `id` in `id 1`.
+    send(r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{"textDocument":{"uri":"file:///a"},"position":{"line":0,"character":19}}}"#);
+    assert!(read_frame(&mut output).contains("int → int"));
+
+    send(r#"{"jsonrpc":"2.0","id":3,"method":"textDocument/definition","params":{"textDocument":{"uri":"file:///a"},"position":{"line":0,"character":19}}}"#);
+    assert!(read_frame(&mut output).contains(
+        r#""range":{"start":{"line":0,"character":4},"end":{"line":0,"character":6}}"#
+    ));
+
+    send(r#"{"jsonrpc":"2.0","id":4,"method":"textDocument/inlayHint","params":{"textDocument":{"uri":"file:///a"},"range":{"start":{"line":0,"character":0},"end":{"line":1,"character":0}}}}"#);
+    assert!(read_frame(&mut output).contains(r#""label":": ∀ 'a. 'a → 'a""#));
+
+    // Documents are checked against the prelude.
+    send(r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///b","languageId":"ml","version":1,"text":"fst (pair 1 (concat \"a\" \"b\"))"}}}"#);
+    assert!(read_frame(&mut output).contains(r#""diagnostics":[]"#));
+    send(r#"{"jsonrpc":"2.0","id":6,"method":"textDocument/hover","params":{"textDocument":{"uri":"file:///b"},"position":{"line":0,"character":1}}}"#);
+    assert!(read_frame(&mut output).contains("int"));
+
+    send(r#"{"jsonrpc":"2.0","id":5,"method":"shutdown"}"#);
+    assert!(read_frame(&mut output).contains(r#""result":null"#));
+    send(r#"{"jsonrpc":"2.0","method":"exit"}"#);
+    assert!(server.wait().unwrap().success());
+}
+
+#[test]
+fn malformed_messages_are_skipped() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_type-infer-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = server.stdin.take().unwrap();
+    let mut output = BufReader::new(server.stdout.take().unwrap());
+
+    // Invalid JSON.
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":1,"#).as_bytes()).unwrap();
+    let response = read_frame(&mut output);
+    assert!(response.contains(r#""id":null"#) && response.contains("-32700"), "{}", response);
+
+    // An escaped multibyte character, which JSON doesn't define as an escape.
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":1,"method":"\é"}"#).as_bytes()).unwrap();
+    assert!(read_frame(&mut output).contains("-32700"));
+
+    // A high surrogate not followed by a low one.
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":1,"method":"\ud800\u0041"}"#).as_bytes()).unwrap();
+    let response = read_frame(&mut output);
+    assert!(response.contains("-32601") && response.contains("unknown method \u{fffd}A"), "{}", response);
+
+    // A response to a request the server never sent is not answered, the
+    // next answer is the one to the request after it.
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":7,"result":null}"#).as_bytes()).unwrap();
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":8,"method":"unknown"}"#).as_bytes()).unwrap();
+    assert!(read_frame(&mut output).contains(r#""id":8"#));
+
+    // No Content-Length, the body runs into the header of the next message.
+    input.write_all(b"Content-Type: application/json\r\n\r\n{}").unwrap();
+    assert!(read_frame(&mut output).contains("-32700"));
+    input.write_all(frame(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#).as_bytes()).unwrap();
+    let response = read_frame(&mut output);
+    assert!(response.contains(r#""id":2"#) && response.contains(r#""result":null"#), "{}", response);
+
+    // A body cut short by the end of the input.
+    input.write_all(b"Content-Length: 100\r\n\r\n{}").unwrap();
+    drop(input);
+    assert!(read_frame(&mut output).contains("-32700"));
+    assert!(server.wait().unwrap().success());
+}
@@ -0,0 +1,112 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Modules checked against each other's interfaces.
+
+use std::collections::BTreeMap;
+
+use type_infer_rs::{
+    error::ModuleError,
+    expr::expr::Ident,
+    module::{check_modules, Module},
+    syntax::parser::{parse_module, parse_signature},
+};
+
+fn name(name: &str) -> Ident {
+    Ident::new(name.to_string())
+}
+
+fn module(module: &str, src: &str) -> Module {
+    parse_module(name(module), src).unwrap()
+}
+
+#[test]
+fn signatures_parse() {
+    let sig = parse_signature("
+        -- Parameters associate to the right.
+        val compose : forall a b c . (b -> c) -> (a -> b) -> a -> c;
+        val n : int;
+        val k : a -> (b -> a);
+    ").unwrap();
+    let scheme = |x| sig.get(&name(x)).unwrap().to_string();
+    assert_eq!(scheme("compose"), "forall a b c . (b -> c) -> (a -> b) -> a -> c");
+    assert_eq!(scheme("n"), "int");
+    assert_eq!(scheme("k"), "forall a b . a -> b -> a");
+
+    let err = parse_signature("val f : forall a . a -> b;").unwrap_err();
+    assert_eq!(err.message, "type variable `b` is not bound");
+    assert!(parse_signature("val f : int").is_err());
+    assert!(parse_signature("let f = 1;").is_err());
+}
+
+#[test]
+fn signatures_hide_and_restrict() {
+    let sig = parse_signature("val id : int -> int;").unwrap();
+    let lib = module("lib", "let helper = λ x. x; let id = λ x. helper x;").with_signature(sig);
+    let main = module("main", "import lib; let a = id 1; let b = helper 1;");
+    let bad = module("bad", "import lib; let c = id true;");
+    let checked = check_modules(&[main, lib, bad]);
+
+    let interface = checked[&name("lib")].as_ref().unwrap();
+    assert_eq!(interface.iter().count(), 1);
+    assert_eq!(interface.get(&name("id")).unwrap().to_string(), "int -> int");
+
+    // `helper` is not exported, and `id` is only known at `int -> int`.
+    assert!(matches!(
+        &checked[&name("main")],
+        Err(ModuleError::Binding(binding, _)) if *binding == name("b"),
+    ));
+    assert!(matches!(&checked[&name("bad")], Err(ModuleError::Binding(..))));
+}
+
+#[test]
+fn implementations_are_as_general_as_signatures() {
+    let sig = parse_signature("val k : forall a b . a -> b -> a;").unwrap();
+    let check = |src| module("m", src).with_signature(sig.clone()).check(&BTreeMap::new());
+    assert!(check("let k = λ x. λ y. x;").is_ok());
+    assert!(matches!(check("let k = λ x. λ y. y;"), Err(ModuleError::NotGeneral { .. })));
+    assert!(matches!(check("let k = λ x. λ y. 1;"), Err(ModuleError::NotGeneral { .. })));
+    assert!(matches!(check("let j = λ x. λ y. x;"), Err(ModuleError::MissingBinding(_))));
+}
+
+#[test]
+fn importers_only_need_signatures() {
+    // The implementation of `lib` is broken, `main` is still checked
+    // against its signature.
+    let sig = parse_signature("val one : int;").unwrap();
+    let lib = module("lib", "let one = true;").with_signature(sig);
+    let main = module("main", "import lib; let two = one;");
+    let checked = check_modules(&[lib, main]);
+    assert!(matches!(&checked[&name("lib")], Err(ModuleError::NotGeneral { .. })));
+    assert_eq!(checked[&name("main")].as_ref().unwrap().get(&name("two")).unwrap().to_string(), "int");
+
+    // Without a signature, the importer has nothing to check against.
+    let lib = module("lib", "let one = 1 1;");
+    let main = module("main", "import lib; let two = one;");
+    let checked = check_modules(&[main, lib]);
+    assert_eq!(checked[&name("main")], Err(ModuleError::DependencyFailed(name("lib"))));
+}
+
+#[test]
+fn import_errors() {
+    let a = module("a", "import b; let x = y;");
+    let b = module("b", "import a; let y = x;");
+    let c = module("c", "import d;");
+    let checked = check_modules(&[a.clone(), b.clone(), c]);
+    assert_eq!(
+        checked[&name("a")],
+        Err(ModuleError::DependencyFailed(name("b"))),
+    );
+    assert_eq!(
+        checked[&name("b")],
+        Err(ModuleError::ImportCycle(vec![name("a"), name("b"), name("a")])),
+    );
+    assert_eq!(checked[&name("c")], Err(ModuleError::UnknownModule(name("d"))));
+
+    // A signature breaks the cycle.
+    let b = b.with_signature(parse_signature("val y : int;").unwrap());
+    let a = a.with_signature(parse_signature("val x : int;").unwrap());
+    let checked = check_modules(&[a, b]);
+    assert!(checked[&name("a")].is_ok());
+    assert!(checked[&name("b")].is_ok());
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Type errors come with notes on the likely mistake, and only when the note
+// is right.
+
+use type_infer_rs::{infer::InferCtxt, prelude, syntax::parser::parse};
+
+/// The notes of the type error of an expression checked against the prelude.
+fn notes(src: &str) -> Vec<String> {
+    let expr = parse(src).unwrap().expr;
+    let err = InferCtxt::new().infer_in(prelude::tcx(), &expr).unwrap_err();
+    err.notes.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn did_you_mean() {
+    assert_eq!(notes("mapp id (cons 1 nil)"), ["did you mean `map`?"]);
+    assert_eq!(notes("λ counter. countr"), ["did you mean `counter`?"]);
+    // Nothing in scope is close enough.
+    assert!(notes("frobnicate 1").is_empty());
+    assert!(notes("λ alpha. omega").is_empty());
+}
+
+#[test]
+fn missing_argument() {
+    assert_eq!(
+        notes("fadd (λ x. 1.5) 2.5"),
+        ["found `?2 -> float`, expected `float`, is an argument missing?"],
+    );
+    // The result of the argument doesn't fit either.
+    assert!(notes("fadd (λ x. true) 2.5").is_empty());
+}
+
+#[test]
+fn swapped_arguments() {
+    let f = "let f = λ x. λ s. concat s (const \"\" x) in";
+    assert_eq!(notes(&format!("{f} f \"a\" 1")), ["the arguments would fit if they were swapped"]);
+    // Swapping the arguments doesn't help.
+    assert!(notes(&format!("{f} f true 1")).is_empty());
+    // A single argument has nothing to be swapped with.
+    assert!(notes("not 1").is_empty());
+    assert!(notes("(λ f. f true) 1").is_empty());
+}
+
+#[test]
+fn extra_argument() {
+    assert_eq!(notes("id 1 2"), ["the callee has type `int`, it is applied to too many arguments"]);
+    assert_eq!(
+        notes("const true 1 2"),
+        ["the callee has type `bool`, it is applied to too many arguments"],
+    );
+}
+
+#[test]
+fn not_a_function() {
+    assert_eq!(notes("1 2"), ["the callee has type `int`, it is not a function"]);
+    assert_eq!(notes("\"f\" 2"), ["the callee has type `string`, it is not a function"]);
+    // A function applied to the wrong argument is not one.
+    assert!(!notes("fadd true").iter().any(|note| note.contains("not a function")));
+}
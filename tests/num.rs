@@ -0,0 +1,119 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Integer literals are `int` or `float`, and default to `int` when nothing
+// else decides.
+
+use type_infer_rs::{
+    error::Warning,
+    eval::{eval_in, Value},
+    infer::InferCtxt,
+    intern,
+    prelude,
+    reference,
+    syntax::parser::parse,
+    ty::{Ty, TyVar, ty_scheme::TyScheme, types::Types},
+};
+
+fn infer(src: &str) -> String {
+    let expr = parse(src).unwrap().expr;
+    match InferCtxt::new().infer_in(prelude::tcx(), &expr) {
+        Ok(ty) => ty.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn literals_are_resolved_by_unification() {
+    assert_eq!(infer("fadd 1 2.5"), "float");
+    assert_eq!(infer("λ x. fadd x 1"), "float -> float");
+    assert_eq!(infer("let half = λ x. fdiv x 2 in half 3"), "float");
+    assert_eq!(infer("sum (cons 1 (cons 2 nil))"), "float");
+    assert_eq!(infer("λ f. f 1 2.5"), "(int -> float -> ?1) -> ?1");
+    assert_eq!(infer("λ g. const (g 1) (g 2.5)"), "(float -> ?1) -> ?1");
+
+    let expr = parse("fsub 10 (fmul 2 1.25)").unwrap().expr;
+    let recovered = InferCtxt::new().infer_recovering_in(prelude::tcx(), &expr);
+    assert_eq!(eval_in(prelude::env(), &recovered.resolve_lits(&expr)), Ok(Value::Float(7.5)));
+}
+
+#[test]
+fn ambiguous_literals_default_to_int() {
+    assert_eq!(infer("1"), "int");
+    assert_eq!(infer("id 1"), "int");
+    assert_eq!(infer("let n = 1 in n"), "int");
+    assert_eq!(infer("let f = λ x. 1 in f"), "?2 -> int");
+
+    // The literal bound by a `let` is not generalized, its uses decide it
+    // for all of them.
+    assert_eq!(infer("let x = 1 in fadd x 1.0"), "float");
+    assert_eq!(infer("let x = 1 in const x (fadd x 1.0)"), "float");
+    assert_eq!(infer("let x = 1 in const x x"), "int");
+    assert_eq!(infer("let f = λ y. 1 in const (f true) (fadd (f ()) 1.0)"), "float");
+    assert_eq!(infer("let x = 1.0 in fadd x 1"), "float");
+    assert_eq!(infer("λ y. let x = y in fadd x 1"), "float -> float");
+}
+
+#[test]
+fn literals_are_not_functions() {
+    assert_eq!(
+        infer("1 true"),
+        "cannot unify int with bool -> ?0\nnote: the callee has type `int`, it is not a function",
+    );
+    assert_eq!(infer("concat 1"), "cannot unify string with int");
+    assert_eq!(infer("λ x. fadd (x 1) (x \"one\")"), "cannot unify int with string");
+}
+
+#[test]
+fn defaulting_is_warned_about() {
+    // `n` is defaulted, the literals used as floats are not.
+    let src = "let n = 1 in fadd 2 (const 3.5 n)";
+    let parsed = parse(src).unwrap();
+    let mut icx = InferCtxt::new();
+    assert_eq!(icx.infer_in(prelude::tcx(), &parsed.expr).unwrap().to_string(), "float");
+    let [(id, Warning::Defaulted(var))] = icx.warnings() else {
+        panic!("expected one warning, found {:?}", icx.warnings());
+    };
+    let span = parsed.spans[id];
+    assert_eq!(&src[span.lo..span.hi], "1");
+    assert_eq!(*var, TyVar::num(0));
+
+    // The warnings of a program inferred with recovery.
+    let src = "(λ x. true) 42";
+    let parsed = parse(src).unwrap();
+    let recovered = InferCtxt::new().infer_recovering(&parsed.expr);
+    assert!(recovered.errors.is_empty());
+    let [(id, warning)] = &recovered.warnings[..] else {
+        panic!("expected one warning, found {:?}", recovered.warnings);
+    };
+    let span = parsed.spans[id];
+    assert_eq!(&src[span.lo..span.hi], "42");
+    assert_eq!(warning.to_string(), "the type ?2 of the literal is ambiguous, it defaults to int");
+}
+
+#[test]
+fn other_checkers_agree() {
+    let srcs = [
+        "1",
+        "λ g. (λ a. λ b. a) (g 1) (g 2.5)",
+        "let f = λ x. 1 in f",
+        "let x = 1 in λ f. (λ a. λ b. a) x ((λ a. λ b. a) (f x) (f 2.5))",
+    ];
+    for src in srcs {
+        let expr = parse(src).unwrap().expr;
+        let ty = InferCtxt::new().infer(&expr);
+        assert_eq!(intern::infer::InferCtxt::new().infer(&expr), ty, "{}", src);
+        let generalize = |ty: Ty| TyScheme::mk_forall(ty.ftv().into_iter().collect(), ty);
+        let reference_ty = reference::infer(&expr).unwrap();
+        assert!(generalize(ty.unwrap()).alpha_eq(&generalize(reference_ty)), "{}", src);
+    }
+    for src in ["1 true", "λ x. (λ a. λ b. a) (x 1) (x true)", "let x = 1 in λ f. (λ a. λ b. a) (f x) (f true)"] {
+        let expr = parse(src).unwrap().expr;
+        assert_eq!(
+            intern::infer::InferCtxt::new().infer(&expr).unwrap_err().kind,
+            InferCtxt::new().infer(&expr).unwrap_err().kind,
+            "{}", src,
+        );
+        assert!(reference::infer(&expr).is_err(), "{}", src);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Programs checked against the prelude.
+
+use std::collections::BTreeMap;
+
+use type_infer_rs::{
+    expr::expr::Ident,
+    infer::InferCtxt,
+    interface::Interface,
+    prelude,
+    program::IncrementalCtxt,
+    syntax::parser::{parse, parse_program},
+};
+
+fn infer(src: &str) -> String {
+    let expr = parse(src).unwrap().expr;
+    match InferCtxt::new().infer_in(prelude::tcx(), &expr) {
+        Ok(ty) => ty.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+fn scheme(name: &str) -> String {
+    prelude::tcx().get(&Ident::new(name.to_string())).unwrap().normalize().to_string()
+}
+
+#[test]
+fn prelude_schemes() {
+    assert_eq!(scheme("id"), "forall a . a -> a");
+    assert_eq!(scheme("compose"), "forall a b c . (a -> b) -> (c -> a) -> c -> b");
+    assert_eq!(scheme("fold"), "forall a b c . a -> b -> (a -> b -> c) -> c");
+    // The prelude is loaded once.
+    assert!(std::ptr::eq(prelude::tcx(), prelude::tcx()));
+    assert!(std::ptr::eq(prelude::env(), prelude::env()));
+}
+
+#[test]
+fn bundled_interface_is_up_to_date() {
+    let interface = prelude::interface();
+    if std::env::var_os("UPDATE_PRELUDE").is_some() {
+        interface.save(concat!(env!("CARGO_MANIFEST_DIR"), "/src/prelude.tyi")).unwrap();
+        return;
+    }
+    let bundled = Interface::read(prelude::INTERFACE, prelude::SOURCE, &BTreeMap::new())
+        .expect("src/prelude.tyi is stale, run this test again with UPDATE_PRELUDE=1");
+    assert_eq!(bundled, interface);
+    for (name, tys) in interface.exports.iter() {
+        assert_eq!(prelude::tcx().get(name), Some(tys), "{}", name);
+    }
+}
+
+#[test]
+fn expressions_use_the_prelude() {
+    assert_eq!(infer("fst (pair 1 true)"), "int");
+    assert_eq!(infer("snd (pair 1 true)"), "bool");
+    assert_eq!(infer("option 0 (λ x. x) (some 1)"), "int");
+    assert_eq!(infer("option false (λ x. x) (map_option (λ x. true) (some 1))"), "bool");
+    assert_eq!(infer("compose (λ x. true) (λ x. 1) false"), "bool");
+
+    // Sum up a list with `const` standing in for addition.
+    let xs = "(cons 1 (cons 2 nil))";
+    assert_eq!(infer(&format!("fold const 0 {}", xs)), "int");
+    assert_eq!(infer(&format!("fold const true (map (λ x. true) {})", xs)), "bool");
+    assert_eq!(infer(&format!("fold const 0 (filter (λ x. ctrue) (append {} {}))", xs, xs)), "int");
+
+    // `map` keeps the element type.
+    assert!(infer(&format!("fold const true (map id {})", xs)).contains("cannot unify"));
+}
+
+#[test]
+fn programs_use_the_prelude() {
+    let program = parse_program("
+        let twice = λ f. compose f f;
+        let n = twice id 1;
+        -- Bindings of the program shadow the prelude.
+        let fst = λ x. x;
+        let m = fst 2;
+    ").unwrap();
+    let checked = IncrementalCtxt::with_globals(prelude::tcx().clone()).check(&program);
+    let scheme = |name: &str| {
+        checked.schemes[&Ident::new(name.to_string())].as_ref().unwrap().to_string()
+    };
+    assert_eq!(scheme("twice"), "forall a . (a -> a) -> a -> a");
+    assert_eq!(scheme("n"), "int");
+    assert_eq!(scheme("m"), "int");
+
+    // Without the prelude, `compose` is unbound.
+    assert!(IncrementalCtxt::new().check(&program).schemes[&Ident::new("twice".to_string())].is_err());
+}
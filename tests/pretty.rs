@@ -0,0 +1,76 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// The pretty printer prints expressions that parse back to themselves, at any
+// width and in both character sets.
+
+use type_infer_rs::{
+    gen::Gen,
+    infer::InferCtxt,
+    pretty::PrettyCtxt,
+    syntax::parser::parse,
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt},
+};
+
+fn targets(gen: &mut Gen) -> Vec<Ty> {
+    let a = Ty::mk_var(TyVar::new("a".to_string()));
+    let mut targets = (0..100).map(|_| gen.gen_ty(4)).collect::<Vec<_>>();
+    targets.push(Ty::mk_arrow(a.clone(), a));
+    targets
+}
+
+#[test]
+fn printed_expressions_parse_back() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    for target in targets(&mut gen) {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        for width in [0, 20, 80] {
+            for unicode in [true, false] {
+                let pcx = PrettyCtxt::new().with_width(width).with_unicode(unicode);
+                let text = pcx.expr(&expr);
+                let parsed = parse(&text)
+                    .unwrap_or_else(|err| panic!("{} doesn't parse: {}", text, err));
+                assert_eq!(parsed.expr.assign_ids(), expr, "{} parses differently", text);
+                assert!(unicode || text.is_ascii(), "{}", text);
+            }
+        }
+    }
+}
+
+#[test]
+fn printed_expressions_fit_the_width() {
+    let mut gen = Gen::new(0x2545_f491_4f6c_dd1d);
+    for target in targets(&mut gen) {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 12) else {
+            continue;
+        };
+        let text = PrettyCtxt::new().with_width(40).expr(&expr);
+        let flat = PrettyCtxt::new().with_width(usize::MAX).expr(&expr);
+        assert!(!flat.contains('\n'));
+        if flat.chars().count() <= 40 {
+            assert_eq!(text, flat);
+        } else {
+            assert!(text.contains('\n'), "{} is not broken", text);
+        }
+    }
+}
+
+#[test]
+fn schemes_are_printed_up_to_renaming() {
+    let mut gen = Gen::new(0x1234_5678_9abc_def0);
+    for target in targets(&mut gen) {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 8) else {
+            continue;
+        };
+        let mut icx = InferCtxt::new();
+        let ty = icx.infer(&expr.assign_ids()).unwrap();
+        let tys = icx.generalize(TyCtxt::new(), ty);
+
+        let pcx = PrettyCtxt::new();
+        assert_eq!(pcx.scheme(&tys), pcx.scheme(&tys.normalize()));
+        assert!(!pcx.scheme(&tys).contains('?'));
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Checking programs of top-level bindings, in parallel and incrementally,
+// with the bindings cached from one check to the next.
+
+use std::collections::BTreeMap;
+
+use type_infer_rs::{
+    prelude,
+    program::{check_parallel, Checked, IncrementalCtxt},
+    syntax::parser::parse_program,
+    ty::ty_ctxt::TyCtxt,
+};
+
+#[test]
+fn parallel_checking_agrees_with_sequential_checking() {
+    // Independent failing bindings, and bindings that depend on them.
+    let src = (0..24)
+        .map(|i| format!(
+            "let id{i} = λ x. x; \
+             let bad{i} = λ f. f f (id{i} 1); \
+             let use{i} = λ y. bad{i} y; \
+             let app{i} = λ g. g 1 (g true);",
+        ))
+        .collect::<String>();
+    let program = parse_program(&src).unwrap();
+
+    let sequential = IncrementalCtxt::new().check(&program).schemes;
+    assert!(sequential.values().any(|tys| tys.as_ref().is_err_and(|err| err.to_string().contains('?'))));
+    for threads in [1, 2, 4, 8] {
+        assert_eq!(
+            check_parallel(&program, &TyCtxt::new(), threads),
+            sequential,
+            "with {} threads",
+            threads,
+        );
+    }
+
+    // The same with the prelude, whose names the bindings use.
+    let src = (0..24)
+        .map(|i| format!(
+            "let id{i} = λ x. x; \
+             let bad{i} = λ s. concat s (id{i} 1); \
+             let use{i} = λ y. fst (pair (bad{i} y) (fadd 1.5 2.5)); \
+             let sum{i} = map (λ n. add n {i}) (cons 1 nil);",
+        ))
+        .collect::<String>();
+    let program = parse_program(&src).unwrap();
+
+    let sequential = IncrementalCtxt::with_globals(prelude::tcx().clone()).check(&program).schemes;
+    assert!(sequential.values().any(|tys| tys.is_err()));
+    assert!(sequential.values().any(|tys| tys.is_ok()));
+    for threads in [1, 2, 4, 8] {
+        assert_eq!(
+            check_parallel(&program, prelude::tcx(), threads),
+            sequential,
+            "with {} threads and the prelude",
+            threads,
+        );
+    }
+}
+
+fn schemes(checked: &Checked) -> BTreeMap<&str, String> {
+    checked.schemes.iter()
+        .map(|(name, tys)| (name.name.as_str(), match tys {
+            Ok(tys) => tys.to_string(),
+            Err(err) => err.to_string(),
+        }))
+        .collect()
+}
+
+fn reinferred(checked: &Checked) -> Vec<&str> {
+    checked.reinferred.iter().map(|name| name.name.as_str()).collect()
+}
+
+#[test]
+fn duplicate_names_are_the_last_binding() {
+    // The shadowed `f` is checked after the `f` that shadows it.
+    let program = parse_program("let f = λ u. h; let f = 1; let h = f; let g = f;").unwrap();
+    let checked = IncrementalCtxt::new().check(&program);
+    assert_eq!(schemes(&checked), BTreeMap::from([
+        ("f", "int".to_string()),
+        ("g", "int".to_string()),
+        ("h", "int".to_string()),
+    ]));
+    assert_eq!(check_parallel(&program, &TyCtxt::new(), 4), checked.schemes);
+}
+
+#[test]
+fn unchanged_groups_are_cached() {
+    let mut incr = IncrementalCtxt::new();
+    let program = parse_program("let id = λ x. x; let k = λ x. λ y. x; let n = id 1;").unwrap();
+    assert_eq!(reinferred(&incr.check(&program)), ["id", "k", "n"]);
+    assert!(incr.check(&program).reinferred.is_empty());
+
+    // Moving and editing `k`, which nothing depends on.
+    let program = parse_program("let k = λ y. λ x. x; let id = λ x. x; let n = id 1;").unwrap();
+    assert_eq!(reinferred(&incr.check(&program)), ["k"]);
+}
+
+#[test]
+fn dependents_are_inferred_again_when_a_scheme_changes() {
+    let mut incr = IncrementalCtxt::new();
+    incr.check(&parse_program("let id = λ x. x; let n = id 1; let m = n;").unwrap());
+
+    // A new source with the same scheme doesn't invalidate the dependents.
+    let checked = incr.check(&parse_program("let id = λ y. y; let n = id 1; let m = n;").unwrap());
+    assert_eq!(reinferred(&checked), ["id"]);
+
+    // A new scheme does, as far as the schemes of the dependents change.
+    let checked = incr.check(&parse_program("let id = λ y. 2; let n = id true; let m = n;").unwrap());
+    assert_eq!(reinferred(&checked), ["id", "n"]);
+    let checked = incr.check(&parse_program("let id = λ y. true; let n = id true; let m = n;").unwrap());
+    assert_eq!(reinferred(&checked), ["id", "m", "n"]);
+    assert_eq!(schemes(&checked)["m"], "bool");
+}
+
+#[test]
+fn mutually_recursive_groups_are_inferred_again_as_a_whole() {
+    let mut incr = IncrementalCtxt::new();
+    let src = "let even = λ n. odd n; let odd = λ n. even n; let f = λ x. even x; let k = 1;";
+    let checked = incr.check(&parse_program(src).unwrap());
+    assert_eq!(reinferred(&checked), ["even", "f", "k", "odd"]);
+    assert_eq!(schemes(&checked)["even"], "forall a b . a -> b");
+
+    // Editing `odd` changes the scheme of `even` too.
+    let src = "let even = λ n. odd n; let odd = λ n. even true; let f = λ x. even x; let k = 1;";
+    let checked = incr.check(&parse_program(src).unwrap());
+    assert_eq!(reinferred(&checked), ["even", "f", "odd"]);
+    assert_eq!(schemes(&checked)["f"], "forall a . bool -> a");
+}
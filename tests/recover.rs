@@ -0,0 +1,100 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Inference with error recovery reports every independent type error once,
+// and none that only the error type causes.
+
+use type_infer_rs::{
+    infer::{InferCtxt, Recovered},
+    prelude,
+    syntax::parser::{parse, Parsed},
+};
+
+fn recover(src: &str) -> (Parsed, Recovered) {
+    let parsed = parse(src).unwrap();
+    let recovered = InferCtxt::new().infer_recovering_in(prelude::tcx(), &parsed.expr);
+    (parsed, recovered)
+}
+
+/// The errors, with the source text of the nodes they are at.
+fn errors<'a>(src: &'a str, parsed: &Parsed, recovered: &Recovered) -> Vec<(&'a str, String)> {
+    recovered.errors.iter()
+        .map(|(id, err)| {
+            let span = parsed.spans[id];
+            (&src[span.lo..span.hi], err.to_string())
+        })
+        .collect()
+}
+
+/// The types of the nodes, in the order of their ids, with their source text.
+fn types<'a>(src: &'a str, parsed: &Parsed, recovered: &Recovered) -> Vec<(&'a str, String)> {
+    recovered.types.iter()
+        .map(|(id, ty)| {
+            let span = parsed.spans[id];
+            (&src[span.lo..span.hi], ty.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn independent_errors_are_all_collected() {
+    let src = "(1 true) (concat 2)";
+    let (parsed, recovered) = recover(src);
+    assert_eq!(errors(src, &parsed, &recovered), [
+        ("1 true", "cannot unify int with bool -> ?1\n\
+            note: the callee has type `int`, it is not a function".to_string()),
+        ("concat 2", "cannot unify string with int".to_string()),
+    ]);
+    // The outer application is not an error, its callee has the error type.
+    assert_eq!(recovered.ty.to_string(), "?0");
+    assert_eq!(types(src, &parsed, &recovered), [
+        ("1", "int"),
+        ("true", "bool"),
+        ("1 true", "<error>"),
+        ("concat", "string -> string -> string"),
+        ("2", "int"),
+        ("concat 2", "<error>"),
+        ("1 true) (concat 2", "?0"),
+    ].map(|(node, ty)| (node, ty.to_string())));
+
+    let src = "pair (undefined 1) (fadd 2.5 \"a\")";
+    let (parsed, recovered) = recover(src);
+    assert_eq!(errors(src, &parsed, &recovered), [
+        ("undefined", "unbound variable undefined".to_string()),
+        ("fadd 2.5 \"a\"", "cannot unify float with string".to_string()),
+    ]);
+}
+
+#[test]
+fn errors_of_the_error_type_are_not_reported() {
+    // `f` returns the error type, which fits both `float` and `string`.
+    let src = "let f = λ x. fadd x true in pair (f 1.5) (concat (f 2.5) \"a\")";
+    let (parsed, recovered) = recover(src);
+    assert_eq!(errors(src, &parsed, &recovered), [
+        ("fadd x true", "cannot unify float with bool".to_string()),
+    ]);
+    assert_eq!(recovered.ty.to_string(), "(<error> -> string -> ?7) -> ?7");
+    let types = types(src, &parsed, &recovered);
+    assert!(types.contains(&("λ x. fadd x true", "float -> <error>".to_string())));
+    assert!(types.contains(&("f 2.5", "<error>".to_string())));
+    assert!(types.contains(&("concat (f 2.5) \"a\"", "string".to_string())));
+
+    // An unbound variable can be applied to anything.
+    let src = "concat (undefined 1 true) \"a\"";
+    let (parsed, recovered) = recover(src);
+    assert_eq!(errors(src, &parsed, &recovered), [
+        ("undefined", "unbound variable undefined".to_string()),
+    ]);
+    assert_eq!(recovered.ty.to_string(), "string");
+}
+
+#[test]
+fn an_error_is_reported_where_unification_fails() {
+    let src = "λ g. pair (g 1) (g true)";
+    let (parsed, recovered) = recover(src);
+    assert_eq!(errors(src, &parsed, &recovered), [
+        ("g true", "cannot unify int with bool".to_string()),
+    ]);
+    assert_eq!(recovered.ty.to_string(), "(int -> ?6) -> (?6 -> <error> -> ?5) -> ?5");
+    assert_eq!(recovered.types[&parsed.expr.id], recovered.ty);
+}
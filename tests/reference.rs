@@ -0,0 +1,54 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Algorithm W must accept exactly the programs that the let-expanding
+// reference checker accepts, with the same types.
+
+use type_infer_rs::{
+    expr::expr::Expr,
+    gen::Gen,
+    infer::InferCtxt,
+    reference,
+    ty::{Ty, ty_ctxt::TyCtxt, ty_scheme::TyScheme, types::Types},
+};
+
+fn generalize(ty: Ty) -> TyScheme {
+    TyScheme::mk_forall(ty.ftv().into_iter().collect(), ty)
+}
+
+/// Runs both checkers, and returns whether the expression is well-typed.
+fn agree(expr: &Expr) -> bool {
+    let expr = expr.clone().assign_ids();
+    match (InferCtxt::new().infer(&expr), reference::infer(&expr)) {
+        (Ok(ty), Ok(reference_ty)) => {
+            assert!(
+                generalize(ty.clone()).alpha_eq(&generalize(reference_ty.clone())),
+                "{} has type {} but the reference type is {}", expr, ty, reference_ty,
+            );
+            true
+        },
+        (Err(_), Err(_)) => false,
+        (ty, reference_ty) =>
+            panic!("{} is inferred as {:?} but checked as {:?}", expr, ty, reference_ty),
+    }
+}
+
+#[test]
+fn random_programs_are_accepted_alike() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    let well_typed = (0..5000).filter(|_| agree(&gen.gen_untyped(6))).count();
+    // Both outcomes are exercised.
+    assert!(well_typed > 100, "only {} programs are well-typed", well_typed);
+    assert!(well_typed < 4900, "{} programs are well-typed", well_typed);
+}
+
+#[test]
+fn generated_programs_are_accepted_alike() {
+    let mut gen = Gen::new(0x6a09_e667_f3bc_c909);
+    for _ in 0..300 {
+        let ty = gen.gen_ty(4);
+        if let Some(expr) = gen.gen_expr(&TyCtxt::new(), &ty, 10) {
+            assert!(agree(&expr));
+        }
+    }
+}
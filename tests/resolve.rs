@@ -0,0 +1,61 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Resolution and unique renaming must not change what variables refer to.
+
+use type_infer_rs::{
+    gen::Gen,
+    infer::InferCtxt,
+    resolve::{rename_unique, resolve},
+    syntax::parser::parse,
+    ty::{ty_ctxt::TyCtxt, ty_scheme::TyScheme, types::Types},
+};
+
+#[test]
+fn shadowing_and_unbound_names_are_reported() {
+    let src = "let f = λ x. x in let f = f in λ x. f y";
+    let parsed = parse(src).unwrap();
+    let resolution = resolve(&parsed.expr);
+
+    let name_at = |id| parsed.spans[&id].lo;
+    let shadowed = resolution.shadowed.iter()
+        .map(|(binder, shadowed)| (name_at(*binder), name_at(*shadowed)))
+        .collect::<Vec<_>>();
+    // The second `f` shadows the first one, the second `x` doesn't shadow
+    // the first one, which is not in scope.
+    assert_eq!(shadowed, [(src.find("let f = f").unwrap(), 0)]);
+    assert_eq!(resolution.unbound.iter().map(|id| name_at(*id)).collect::<Vec<_>>(), [src.find('y').unwrap()]);
+}
+
+#[test]
+fn renaming_keeps_types_and_bindings() {
+    let mut gen = Gen::new(0x1405_7b7e_f767_814f);
+    for _ in 0..200 {
+        let ty = gen.gen_ty(4);
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &ty, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        let renamed = rename_unique(&expr);
+        assert_eq!(renamed.id, expr.id);
+
+        // The types are the same up to the names of the type variables.
+        let scheme = |ty| TyScheme::mk_forall(Vec::new(), ty);
+        let tys = scheme(InferCtxt::new().infer(&expr).unwrap());
+        let renamed_tys = scheme(InferCtxt::new().infer(&renamed).unwrap());
+        let generalize = |tys: TyScheme| TyScheme::mk_forall(tys.ftv().into_iter().collect(), tys.ty);
+        assert!(generalize(tys).alpha_eq(&generalize(renamed_tys)), "{}", expr);
+
+        // Every variable still refers to the same binder, and no binder
+        // shadows another.
+        let resolution = resolve(&renamed);
+        assert_eq!(resolution.bindings, resolve(&expr).bindings);
+        assert!(resolution.shadowed.is_empty());
+    }
+}
+
+#[test]
+fn renamed_binders_avoid_unbound_names() {
+    let expr = parse("(λ y. y) y").unwrap().expr;
+    assert_eq!(rename_unique(&expr).to_string(), "(λ y1. y1 y)");
+}
@@ -0,0 +1,122 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Comparing principal types: every instantiation `Inst` makes is an instance
+// of the scheme it instantiates, and normalized schemes don't depend on the
+// fresh variables of a run.
+
+use type_infer_rs::{
+    expr::expr::Expr,
+    gen::Gen,
+    infer::InferCtxt,
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, subst::Subst, types::Types},
+};
+
+fn var(name: &str) -> Ty {
+    Ty::mk_var(TyVar::new(name.to_string()))
+}
+
+/// Principal type schemes of random well-typed expressions.
+fn schemes(seed: u64) -> Vec<(Expr, TyScheme)> {
+    let mut gen = Gen::new(seed);
+    let mut targets = (0..100).map(|_| gen.gen_ty(4)).collect::<Vec<_>>();
+    targets.push(Ty::mk_arrow(var("a"), var("a")));
+    targets.push(Ty::mk_arrow(var("a"), Ty::mk_arrow(var("b"), var("a"))));
+    targets
+        .iter()
+        .filter_map(|target| gen.gen_expr(&TyCtxt::new(), target, 8))
+        .map(|expr| {
+            let expr = expr.assign_ids();
+            let mut icx = InferCtxt::new();
+            let ty = icx.infer(&expr).unwrap();
+            let tys = icx.generalize(TyCtxt::new(), ty);
+            (expr, tys)
+        })
+        .collect()
+}
+
+#[test]
+fn instantiation_is_an_instance() {
+    let mut gen = Gen::new(7);
+    for (_, tys) in schemes(0x9e37_79b9_7f4a_7c15) {
+        let mut icx = InferCtxt::new();
+        let ty = icx.instantiate(tys.clone());
+        assert!(TyScheme::mk_forall(vec![], ty.clone()).is_instance_of(&tys));
+
+        // Generalizing the instantiation gives the scheme back.
+        let regeneralized = icx.generalize(TyCtxt::new(), ty);
+        assert!(regeneralized.alpha_eq(&tys), "{} and {}", regeneralized, tys);
+
+        // So does substituting ground types for the bound variables.
+        let mut subst = Subst::identity();
+        for var in &tys.vars {
+            subst.insert(var.clone(), gen.gen_ty(2));
+        }
+        let ground = TyScheme::mk_forall(vec![], tys.ty.apply(&subst));
+        assert!(ground.is_instance_of(&tys), "{} is not an instance of {}", ground, tys);
+        assert!(ground.ftv().is_empty());
+    }
+}
+
+#[test]
+fn instance_is_a_preorder() {
+    let schemes = schemes(0x2545_f491_4f6c_dd1d)
+        .into_iter()
+        .map(|(_, tys)| tys)
+        .take(40)
+        .collect::<Vec<_>>();
+    for s1 in &schemes {
+        assert!(s1.is_instance_of(s1));
+        for s2 in &schemes {
+            // Schemes that are instances of each other are alpha-equivalent.
+            if s1.is_instance_of(s2) && s2.is_instance_of(s1) {
+                assert!(s1.alpha_eq(s2), "{} and {}", s1, s2);
+            }
+            for s3 in &schemes {
+                if s1.is_instance_of(s2) && s2.is_instance_of(s3) {
+                    assert!(s1.is_instance_of(s3), "{} ⊑ {} ⊑ {}", s1, s2, s3);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn normalized_schemes_do_not_depend_on_fresh_variables() {
+    for (expr, tys) in schemes(0x1234_5678_9abc_def0) {
+        // Using up some fresh variables first changes the raw result.
+        let mut icx = InferCtxt::new();
+        icx.infer(&expr).unwrap();
+        let ty = icx.infer(&expr).unwrap();
+        let again = icx.generalize(TyCtxt::new(), ty);
+
+        assert!(again.alpha_eq(&tys));
+        assert_eq!(again.normalize(), tys.normalize());
+    }
+}
+
+#[test]
+fn free_variables_are_not_instantiated() {
+    // forall a . a -> b
+    let general = TyScheme::mk_forall(
+        vec![TyVar::new("a".to_string())],
+        Ty::mk_arrow(var("a"), var("b")),
+    );
+
+    assert!(TyScheme::mk_forall(vec![], Ty::mk_arrow(Ty::mk_int(), var("b")))
+        .is_instance_of(&general));
+    assert!(!TyScheme::mk_forall(vec![], Ty::mk_arrow(Ty::mk_int(), Ty::mk_int()))
+        .is_instance_of(&general));
+
+    // forall b . b -> b is not an instance, its b is not the free b.
+    assert!(!TyScheme::mk_forall(
+        vec![TyVar::new("b".to_string())],
+        Ty::mk_arrow(var("b"), var("b")),
+    ).is_instance_of(&general));
+
+    // Normalizing doesn't capture the free b.
+    assert_eq!(
+        TyScheme::mk_forall(vec![TyVar::Unknown(1)], Ty::mk_arrow(Ty::mk_var(TyVar::Unknown(1)), var("a"))).normalize(),
+        TyScheme::mk_forall(vec![TyVar::new("b".to_string())], Ty::mk_arrow(var("b"), var("a"))),
+    );
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// With the `serde` feature, expressions, types and substitutions serialize
+// and deserialize back to themselves.
+//
+// $ cargo test --features serde --test serde
+
+#![cfg(feature = "serde")]
+
+use serde::{de::DeserializeOwned, Serialize};
+use type_infer_rs::{
+    expr::expr::Expr,
+    infer::InferCtxt,
+    prelude,
+    syntax::parser::parse,
+    ty::{Ty, ty_ctxt::TyCtxt, ty_scheme::TyScheme, subst::Subst},
+};
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: &T) {
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value, "{} reads differently", json);
+}
+
+#[test]
+fn values_round_trip() {
+    let parsed = parse(r#"let k = λ x. λ y. x in k (λ z. fadd z 1) "a\"b" 'c' () true"#).unwrap();
+    round_trip(&parsed.expr);
+    round_trip::<Expr>(&parse("_hole 2.5").unwrap().expr);
+
+    let recovered = InferCtxt::new().infer_recovering_in(prelude::tcx(), &parsed.expr);
+    for ty in recovered.types.values() {
+        round_trip::<Ty>(ty);
+    }
+    round_trip::<TyCtxt>(prelude::tcx());
+    for (_, tys) in prelude::tcx().iter() {
+        round_trip::<TyScheme>(tys);
+    }
+
+    let mut subst = Subst::identity();
+    for (i, (_, tys)) in prelude::tcx().iter().enumerate() {
+        if let Some(var) = tys.vars.first() {
+            subst.insert(var.clone(), Ty::mk_arrow(Ty::mk_int(), tys.ty.clone()));
+        }
+        if i > 8 {
+            break;
+        }
+    }
+    assert!(subst != Subst::identity());
+    round_trip(&subst);
+}
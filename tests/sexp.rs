@@ -0,0 +1,108 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// The S-expression format reads back what it writes, and test fixtures can be
+// written in it.
+
+use type_infer_rs::{
+    expr::{expr::{Expr, Ident}, expr_id::ExprId},
+    gen::Gen,
+    infer::InferCtxt,
+    syntax::parser::parse,
+    syntax::sexp::{read, FromSexp, Sexp, ToSexp},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt, ty_scheme::TyScheme, subst::Subst},
+};
+
+fn round_trip<T: ToSexp + FromSexp + PartialEq + std::fmt::Debug>(value: &T) {
+    let text = value.to_sexp().to_string();
+    assert_eq!(&read::<T>(&text).unwrap(), value, "{} reads differently", text);
+}
+
+#[test]
+fn generated_values_round_trip() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..200 {
+        let target = gen.gen_ty(4);
+        round_trip(&target);
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 10) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        round_trip(&expr);
+
+        let mut icx = InferCtxt::new();
+        let ty = icx.infer(&expr).unwrap();
+        round_trip(&ty);
+        let tys = icx.generalize(TyCtxt::new(), ty);
+        round_trip(&tys);
+
+        let mut subst = Subst::identity();
+        let mut tcx = TyCtxt::new();
+        for (i, var) in tys.vars.iter().enumerate() {
+            subst.insert(var.clone(), gen.gen_ty(2));
+            tcx.insert(Ident::new(format!("x{}", i)), tys.clone());
+        }
+        round_trip(&subst);
+        round_trip(&tcx);
+    }
+}
+
+#[test]
+fn parsed_expressions_round_trip_with_their_ids() {
+    // The parser numbers the children of a node before the node itself.
+    let parsed = parse("let k = λ x. λ y. x in k (λ z. z) 1").unwrap();
+    let text = parsed.expr.to_sexp().to_string();
+    let expr = read::<Expr>(&text).unwrap();
+    assert_eq!(expr, parsed.expr, "{} reads differently", text);
+    assert!(expr.id != ExprId::from_u32(1));
+
+    // The types of the nodes are found with the ids read back.
+    let recovered = InferCtxt::new().infer_recovering(&parsed.expr);
+    assert!(recovered.types.contains_key(&expr.id));
+
+    // Expressions without ids are numbered, ids are not mixed.
+    let expr = read::<Expr>("(app (lam x (var x)) (int 1))").unwrap();
+    assert_eq!(expr, expr.clone().assign_ids());
+    assert!(read::<Expr>("(@ 3 (app (@ 1 (var f)) (var x)))").is_err());
+    assert!(read::<Expr>("(@ 1 (@ 2 (var x)))").is_err());
+}
+
+#[test]
+fn odd_names_round_trip() {
+    for name in ["", "a b", "(", "\"quoted\"", "back\\slash", "semi;colon", "new\nline", "λ"] {
+        let ident = Ident::new(name.to_string());
+        round_trip(&Expr::mk_abs(ident.clone(), Expr::mk_hole(Some(ident))).assign_ids());
+        round_trip(&Ty::mk_var(TyVar::new(name.to_string())));
+    }
+}
+
+#[test]
+fn errors_point_at_the_offending_node() {
+    let err = read::<Expr>("(app (var f) (lam x))").unwrap_err();
+    assert_eq!((err.span.lo, err.span.hi, err.message.as_str()), (13, 20, "expected 2 arguments, found 1"));
+
+    let err = read::<Ty>("(-> int text)").unwrap_err();
+    assert_eq!((err.span.lo, err.span.hi, err.message.as_str()), (8, 12, "unknown type `text`"));
+
+    assert!(read::<Expr>("(var x) (var y)").is_err());
+    assert!(read::<Expr>("(var \\x)").is_err());
+}
+
+#[test]
+fn inference_fixtures() {
+    let src = include_str!("fixtures/infer.sexp");
+    let cases = Sexp::parse_all(src).unwrap();
+    assert_eq!(cases.len(), 5);
+
+    for case in &cases {
+        let (head, args) = case.as_form().unwrap();
+        assert_eq!(head, "infer");
+        let expr = Expr::from_sexp(&args[0]).unwrap();
+        let expected = TyScheme::from_sexp(&args[1]).unwrap();
+
+        let mut icx = InferCtxt::new();
+        let ty = icx.infer(&expr).unwrap();
+        let tys = icx.generalize(TyCtxt::new(), ty);
+        assert!(tys.alpha_eq(&expected), "{} has type {}, expected {}", expr, tys, expected);
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Type error slices are found exactly for the programs that inference
+// rejects.
+
+use type_infer_rs::{infer::InferCtxt, slice::error_slice, syntax::parser::parse};
+
+#[test]
+fn integer_literals_are_not_a_conflict_at_float() {
+    for src in [
+        "(λ i. (λ u. i 1) (i 2.5)) (λ x. x)",
+        "λ f. (λ u. f 1) (f 2.5)",
+        "let x = 1 in (λ u. x) (λ f. f x 2.5)",
+    ] {
+        let expr = parse(src).unwrap().expr;
+        assert!(InferCtxt::new().infer(&expr).is_ok(), "{}", src);
+        assert_eq!(error_slice(&expr), None, "{}", src);
+    }
+}
+
+#[test]
+fn integer_literals_conflict_with_other_types() {
+    let src = "λ f. (λ u. f 1) (f true)";
+    let parsed = parse(src).unwrap();
+    assert!(InferCtxt::new().infer(&parsed.expr).is_err());
+    let slice = error_slice(&parsed.expr).unwrap();
+    let mut nodes = slice.iter()
+        .map(|id| {
+            let span = parsed.spans[id];
+            &src[span.lo..span.hi]
+        })
+        .collect::<Vec<_>>();
+    nodes.sort();
+    assert!(nodes.contains(&"1") && nodes.contains(&"true"), "{:?}", nodes);
+
+    // The copies of a let-bound literal share its type.
+    let expr = parse("let x = 1 in λ f. (λ u. f x) (f true)").unwrap().expr;
+    assert!(error_slice(&expr).is_some());
+}
@@ -0,0 +1,116 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Elaborated terms are validated by the System F type checker, which must
+// agree with inference.
+
+use type_infer_rs::{
+    expr::expr::{Expr, Ident},
+    gen::Gen,
+    infer::InferCtxt,
+    syntax::parser::parse,
+    system_f::{FTy, Term, check::type_of, elab::elaborate},
+    ty::{Ty, TyVar, ty_ctxt::TyCtxt},
+};
+
+fn var(name: &str) -> Ty {
+    Ty::mk_var(TyVar::new(name.to_string()))
+}
+
+#[test]
+fn elaborated_terms_have_the_inferred_type() {
+    let mut gen = Gen::new(0x9e37_79b9_7f4a_7c15);
+    let mut targets = (0..300).map(|_| gen.gen_ty(4)).collect::<Vec<_>>();
+    targets.push(Ty::mk_arrow(var("a"), var("a")));
+    targets.push(Ty::mk_arrow(var("a"), Ty::mk_arrow(var("b"), var("a"))));
+
+    for target in targets {
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &target, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        let ty = InferCtxt::new().infer(&expr).unwrap();
+        let term = elaborate(&expr).unwrap();
+        let f_ty = type_of(&term)
+            .unwrap_or_else(|err| panic!("{} elaborates to ill-typed {}: {}", expr, term, err));
+
+        // The free type variables are abstracted at the top.
+        let mut vars = Vec::new();
+        let mut inner = &term;
+        while let Term::TyAbs { var, body } = inner {
+            vars.push(var.clone());
+            inner = body;
+        }
+        let expected = vars.into_iter()
+            .rev()
+            .fold(FTy::from(&ty), |ty, var| FTy::mk_forall(var, ty));
+        assert!(f_ty.alpha_eq(&expected), "{} has type {}, expected {}", term, f_ty, expected);
+    }
+}
+
+#[test]
+fn ill_typed_expressions_are_not_elaborated() {
+    let x = Ident::new("x".to_string());
+    // λ x. x x
+    let expr = Expr::mk_abs(
+        x.clone(),
+        Expr::mk_app(Expr::mk_var(x.clone()), Expr::mk_var(x)),
+    ).assign_ids();
+    assert!(elaborate(&expr).is_err());
+}
+
+#[test]
+fn checker_rejects_ill_typed_terms() {
+    let a = TyVar::new("a".to_string());
+    let b = TyVar::new("b".to_string());
+    let x = Ident::new("x".to_string());
+    let id = Term::mk_ty_abs(
+        a.clone(),
+        Term::mk_abs(x.clone(), FTy::Var(a.clone()), Term::Var(x.clone())),
+    );
+
+    // A missing type application.
+    assert!(type_of(&Term::mk_app(id.clone(), Term::Var(x.clone()))).is_err());
+    // A type application to a monomorphic term.
+    assert!(type_of(&Term::mk_ty_app(
+        Term::mk_ty_app(id.clone(), FTy::Int),
+        FTy::Int,
+    )).is_err());
+    // A wrong annotation on a `let`.
+    assert!(type_of(&Term::mk_let(
+        x.clone(),
+        FTy::mk_forall(b.clone(), FTy::mk_arrow(FTy::Var(b.clone()), FTy::Int)),
+        id.clone(),
+        Term::Var(x.clone()),
+    )).is_err());
+    // The same annotation up to renaming is fine.
+    assert!(type_of(&Term::mk_let(
+        x.clone(),
+        FTy::mk_forall(b.clone(), FTy::mk_arrow(FTy::Var(b.clone()), FTy::Var(b))),
+        id,
+        Term::Var(x),
+    )).is_ok());
+}
+
+#[test]
+fn substitution_avoids_capture() {
+    let a = TyVar::new("a".to_string());
+    let b = TyVar::new("b".to_string());
+    // (forall b . a -> b)[a := b] = forall b' . b -> b'
+    let ty = FTy::mk_forall(b.clone(), FTy::mk_arrow(FTy::Var(a.clone()), FTy::Var(b.clone())));
+    let substituted = ty.subst(&a, &FTy::Var(b.clone()));
+    let c = TyVar::new("c".to_string());
+    assert!(substituted.alpha_eq(&FTy::mk_forall(
+        c.clone(),
+        FTy::mk_arrow(FTy::Var(b), FTy::Var(c)),
+    )));
+}
+
+#[test]
+fn integer_literals_are_elaborated_at_their_type() {
+    // `1` is a float, because `i` is also applied to `2.5`.
+    let expr = parse("(λ i. (λ u. i 1) (i 2.5)) (λ x. x)").unwrap().expr;
+    let term = elaborate(&expr).unwrap();
+    assert_eq!(type_of(&term), Ok(FTy::Float));
+    assert!(term.to_string().contains("1.0"), "{}", term);
+}
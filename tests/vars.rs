@@ -0,0 +1,84 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Substitution of expressions for variables on random well-typed programs.
+
+use type_infer_rs::{
+    expr::{expr::{Expr, ExprKind, Ident}, vars::captures},
+    gen::Gen,
+    infer::InferCtxt,
+    resolve::rename_unique,
+    syntax::parser::parse,
+    ty::{Ty, ty_ctxt::TyCtxt, ty_scheme::TyScheme, types::Types},
+};
+
+fn generalize(ty: Ty) -> TyScheme {
+    TyScheme::mk_forall(ty.ftv().into_iter().collect(), ty)
+}
+
+#[test]
+fn inlining_a_let_keeps_its_type() {
+    let mut gen = Gen::new(0xd1b5_4a32_d192_ed03);
+    let mut checked = 0;
+    for _ in 0..300 {
+        let ty = gen.gen_ty(4);
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &ty, 12) else {
+            continue;
+        };
+        // `let x = e0 in e1` has the type of `e1[x := e0]`.
+        let ExprKind::Let { name, value, body } = &expr.kind else {
+            continue;
+        };
+        let inlined = body.subst(name, value).assign_ids();
+        let expr = expr.clone().assign_ids();
+        assert!(expr.fv().is_empty());
+        assert!(inlined.fv().is_empty());
+        let ty = InferCtxt::new().infer(&expr).unwrap();
+        let inlined_ty = InferCtxt::new().infer(&inlined).unwrap();
+        assert!(generalize(ty).alpha_eq(&generalize(inlined_ty)), "{} and {}", expr, inlined);
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn substitution_avoids_capture() {
+    let expr = parse("λ y. let z = x in λ y1. x y y1 z").unwrap().expr;
+    let y = Expr::mk_var(Ident::new("y".to_string()));
+    let substituted = expr.subst(&Ident::new("x".to_string()), &y);
+    assert!(substituted.alpha_eq(&parse("λ a. let z = y in λ b. y a b z").unwrap().expr));
+
+    // Bound occurrences are not substituted.
+    let expr = parse("λ x. x").unwrap().expr;
+    assert!(expr.subst(&Ident::new("x".to_string()), &y).alpha_eq(&expr));
+}
+
+#[test]
+fn renaming_is_alpha_equivalent() {
+    let mut gen = Gen::new(0x94d0_49bb_1331_11eb);
+    for _ in 0..200 {
+        let ty = gen.gen_ty(4);
+        let Some(expr) = gen.gen_expr(&TyCtxt::new(), &ty, 12) else {
+            continue;
+        };
+        let expr = expr.assign_ids();
+        assert!(rename_unique(&expr).alpha_eq(&expr));
+    }
+}
+
+#[test]
+fn lambdas_capture_enclosing_binders() {
+    let src = "let k = 1 in λ x. let f = λ y. k x in λ z. f z";
+    let parsed = parse(src).unwrap();
+    let captured = captures(&parsed.expr)
+        .into_iter()
+        .map(|(id, vars)| {
+            let vars = vars.into_iter().map(|var| var.name).collect::<Vec<_>>();
+            (parsed.spans[&id].lo, vars)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(captured.len(), 3);
+    assert!(captured.contains(&(src.find("λ x").unwrap(), vec!["k".to_string()])));
+    assert!(captured.contains(&(src.find("λ y").unwrap(), vec!["k".to_string(), "x".to_string()])));
+    assert!(captured.contains(&(src.find("λ z").unwrap(), vec!["f".to_string()])));
+}
@@ -0,0 +1,83 @@
+// Copyright (c) Kk Shinkai. All Rights Reserved. See LICENSE.txt in the project
+// root for license information.
+//
+// Small passes written as visitors.
+
+use type_infer_rs::{
+    expr::expr::{Expr, ExprKind, Ident},
+    syntax::parser::parse,
+    ty::{Ty, TyVar},
+    visit::{self, Visit, fold::{self, Fold}, visit_mut::{self, VisitMut}},
+};
+
+/// Collects the names of the variables, in the order they are used.
+struct Uses(Vec<String>);
+
+impl Visit for Uses {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let ExprKind::Var(name) = &expr.kind {
+            self.0.push(name.name.clone());
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Desugars `let x = e0 in e1` into `(λ x. e1) e0`, the application keeps the
+/// id of the `let`.
+struct LetToApp;
+
+impl Fold for LetToApp {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        let expr = fold::walk_expr(self, expr);
+        match expr.kind {
+            ExprKind::Let { name, value, body } => Expr {
+                kind: ExprKind::App {
+                    callee: Box::new(Expr::mk_abs(name, *body)),
+                    arg: value,
+                },
+                id: expr.id,
+            },
+            kind => Expr { kind, id: expr.id },
+        }
+    }
+}
+
+#[test]
+fn visit_walks_in_order() {
+    let expr = parse("let f = λ x. x in f (λ y. f y) z").unwrap().expr;
+    let mut uses = Uses(Vec::new());
+    uses.visit_expr(&expr);
+    assert_eq!(uses.0, ["x", "f", "f", "y", "z"]);
+}
+
+#[test]
+fn fold_rebuilds_the_tree() {
+    let expr = parse("let f = λ x. x in let y = f 1 in f y").unwrap().expr;
+    let desugared = LetToApp.fold_expr(expr.clone());
+    assert_eq!(desugared.to_string(), "(λ f. (λ y. (f y) (f 1)) λ x. x)");
+    assert_eq!(desugared.id, expr.id);
+}
+
+#[test]
+fn visit_mut_updates_in_place() {
+    /// Renames the variable `a` to `b`.
+    struct AToB;
+
+    impl VisitMut for AToB {
+        fn visit_ty_var_mut(&mut self, var: &mut TyVar) {
+            if *var == TyVar::new("a".to_string()) {
+                *var = TyVar::new("b".to_string());
+            }
+        }
+    }
+
+    let a = Ty::mk_var(TyVar::new("a".to_string()));
+    let mut ty = Ty::mk_arrow(Ty::mk_arrow(a.clone(), Ty::mk_int()), a);
+    AToB.visit_ty_mut(&mut ty);
+    assert_eq!(ty.to_string(), "(b -> int) -> b");
+
+    // Walking an expression doesn't touch its names.
+    let mut expr = Expr::mk_abs(Ident::new("a".to_string()), Expr::mk_var(Ident::new("a".to_string())));
+    visit_mut::walk_expr(&mut AToB, &mut expr);
+    assert_eq!(expr.to_string(), "λ a. a");
+}